@@ -8,7 +8,7 @@ use iced::{
 };
 
 use ffimage::{
-    color::Rgb,
+    color::{Rgb, Rgba},
     iter::{BytesExt, ColorConvertExt, PixelsExt},
 };
 
@@ -16,9 +16,6 @@ mod parser;
 mod ppm;
 mod y4m;
 
-mod rgba;
-use rgba::Rgba;
-
 #[derive(Debug)]
 enum App {
     Empty,
@@ -3,6 +3,7 @@ use crate::parser::*;
 pub struct Ppm {
     pub width: u32,
     pub height: u32,
+    #[allow(dead_code)]
     pub range: u32,
     pub bytes: Vec<u8>,
 }
@@ -12,16 +13,8 @@ pub fn read(bytes: impl IntoIterator<Item = u8>) -> Option<Result<Ppm, &'static
 
     // parse format from first line
     let mut magic = [0u8; 2];
-    magic[0] = if let Some(byte) = bytes.next() {
-        byte
-    } else {
-        return None;
-    };
-    magic[1] = if let Some(byte) = bytes.next() {
-        byte
-    } else {
-        return None;
-    };
+    magic[0] = bytes.next()?;
+    magic[1] = bytes.next()?;
 
     // is this a P6 PPM?
     if magic != *b"P6" {
@@ -65,9 +58,9 @@ pub fn read(bytes: impl IntoIterator<Item = u8>) -> Option<Result<Ppm, &'static
     }
 
     Some(Ok(Ppm {
-        width: width as u32,
-        height: height as u32,
-        range: range as u32,
+        width,
+        height,
+        range,
         bytes,
     }))
 }
@@ -134,12 +134,14 @@ impl ColorSpace {
     }
 }
 
+type Fraction = (u32, u32);
+
 pub fn parse_params(
     bytes: &mut impl Iterator<Item = u8>,
 ) -> Option<(Result<Param, &'static str>, u8)> {
     fn parse_fraction(
         bytes: &mut impl Iterator<Item = u8>,
-    ) -> Option<(Result<(u32, u32), &'static str>, u8)> {
+    ) -> Option<(Result<Fraction, &'static str>, u8)> {
         let (res, other) = parse_u32(bytes)?;
         let num = match res {
             Ok(num) => num,
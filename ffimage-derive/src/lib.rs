@@ -0,0 +1,235 @@
+//! Derive macros for [ffimage](https://docs.rs/ffimage) pixel types.
+//!
+//! Pixel types are tuple structs which wrap an array of samples. `#[derive(Pixel)]` generates
+//! the boilerplate every such type needs: `From<[T; N]>`, `Deref` / `DerefMut` to the array and
+//! the `Pixel` trait. If the struct is `#[repr(transparent)]`, it also implements
+//! `ffimage::cast::Transparent` so buffers can be reinterpreted as pixels in place. Casts of
+//! structs whose further fields are not zero-sized markers fail to compile:
+//!
+//! ```compile_fail
+//! use ffimage_derive::Pixel;
+//!
+//! #[derive(Clone, Copy, Pixel)]
+//! #[repr(transparent)]
+//! struct Evil(pub [u8; 0], u64);
+//!
+//! let buf = [0u8; 16];
+//! let _ = ffimage::cast::as_pixels::<Evil>(&buf[2..10]);
+//! ```
+//!
+//! The optional `#[pixel(..)]` attribute accepts:
+//! * `channels(a, b, ..)`: generates accessors (e.g. `a()` and `a_mut()`) for named channels.
+//!   A channel can be mapped to an index, e.g. `channels(r = R, g = G, b = B)` where `R`, `G` and
+//!   `B` are const generics which describe the channel order.
+//! * `subpixels = N`: number of image pixels covered by one pixel, e.g. 2 for YUV 4:2:2.
+//!
+//! ```
+//! use ffimage_derive::Pixel;
+//!
+//! /// Vendor specific BGR pixel with a padding byte
+//! #[derive(Debug, Clone, Copy, PartialEq, Eq, Pixel)]
+//! #[repr(transparent)]
+//! #[pixel(channels(r = R, g = G, b = B))]
+//! pub struct Rgbx<T, const R: usize = 2, const G: usize = 1, const B: usize = 0>(pub [T; 4]);
+//!
+//! let mut pix = Rgbx::<u8>::from([10, 20, 30, 0]);
+//! *pix.g_mut() = 40;
+//!
+//! assert_eq!(*pix.r(), 30);
+//! assert_eq!(*pix, [10, 40, 30, 0]);
+//! assert_eq!(<Rgbx<u8> as ffimage::Pixel>::CHANNELS, 4);
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Error, Expr, Fields,
+    Ident, LitInt, Token, Type,
+};
+
+/// Derives the ffimage pixel traits for a tuple struct which wraps an array of samples.
+///
+/// See the [crate level documentation](crate) for the supported attributes.
+#[proc_macro_derive(Pixel, attributes(pixel))]
+pub fn derive_pixel(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Named channel and its index in the sample array
+struct Channel {
+    name: Ident,
+    index: Expr,
+}
+
+/// Contents of the `#[pixel(..)]` attributes
+#[derive(Default)]
+struct Attrs {
+    channels: Vec<Channel>,
+    subpixels: Option<LitInt>,
+}
+
+impl Attrs {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut attrs = Attrs::default();
+
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("pixel"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("subpixels") {
+                    attrs.subpixels = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("channels") {
+                    meta.parse_nested_meta(|channel| {
+                        let name = channel.path.require_ident()?.clone();
+                        let index = if channel.input.peek(Token![=]) {
+                            channel.value()?.parse()?
+                        } else {
+                            let index = attrs.channels.len();
+                            parse_quote!(#index)
+                        };
+                        attrs.channels.push(Channel { name, index });
+                        Ok(())
+                    })
+                } else {
+                    Err(meta.error("unsupported pixel attribute"))
+                }
+            })?;
+        }
+
+        Ok(attrs)
+    }
+}
+
+/// Returns whether the struct is declared as `#[repr(transparent)]`.
+fn is_transparent(input: &DeriveInput) -> bool {
+    input.attrs.iter().any(|attr| {
+        attr.path().is_ident("repr")
+            && attr
+                .parse_args::<Ident>()
+                .is_ok_and(|repr| repr == "transparent")
+    })
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let attrs = Attrs::parse(input)?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Unnamed(fields) => &fields.unnamed,
+            _ => return Err(Error::new(input.span(), "expected a tuple struct")),
+        },
+        _ => return Err(Error::new(input.span(), "expected a tuple struct")),
+    };
+    let (array, sample, len) = match fields.first().map(|field| &field.ty) {
+        Some(Type::Array(array)) => (array, &array.elem, &array.len),
+        _ => {
+            return Err(Error::new(
+                fields.span(),
+                "expected an array as first field, e.g. `struct Rgb<T>(pub [T; 3])`",
+            ))
+        }
+    };
+    // further fields are markers, e.g. PhantomData
+    let markers = fields
+        .iter()
+        .skip(1)
+        .map(|_| quote!(::core::default::Default::default()));
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let subpixels = attrs
+        .subpixels
+        .map(|subpixels| quote!(const SUBPIXELS: u8 = #subpixels;));
+
+    let accessors = attrs.channels.iter().map(|Channel { name, index }| {
+        let name_mut = format_ident!("{}_mut", name);
+        let doc = format!("Returns the `{}` channel.", name);
+        let doc_mut = format!("Returns the `{}` channel mutably.", name);
+        quote! {
+            #[doc = #doc]
+            #vis fn #name(&self) -> &#sample {
+                &self.0[#index]
+            }
+
+            #[doc = #doc_mut]
+            #vis fn #name_mut(&mut self) -> &mut #sample {
+                &mut self.0[#index]
+            }
+        }
+    });
+    let accessors = if attrs.channels.is_empty() {
+        None
+    } else {
+        Some(quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                #(#accessors)*
+            }
+        })
+    };
+
+    let transparent = if is_transparent(input) {
+        let mut generics = input.generics.clone();
+        let predicates = &mut generics.make_where_clause().predicates;
+        predicates.push(parse_quote!(Self: ::core::marker::Copy));
+        predicates.push(parse_quote!(#sample: ::core::marker::Copy));
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        Some(quote! {
+            // SAFETY: transparent wrapper of the sample array and zero-sized markers
+            unsafe impl #impl_generics ::ffimage::cast::Transparent
+                for #name #ty_generics #where_clause
+            {
+                type Sample = #sample;
+
+                const LAYOUT: () = ::core::assert!(
+                    ::core::mem::size_of::<Self>() > 0
+                        && ::core::mem::size_of::<Self>() == ::core::mem::size_of::<#array>()
+                        && ::core::mem::align_of::<Self>() == ::core::mem::align_of::<#sample>(),
+                    "fields besides the sample array must be zero-sized and aligned no stricter than the samples"
+                );
+            }
+        })
+    } else {
+        None
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::From<#array> for #name #ty_generics #where_clause {
+            fn from(value: #array) -> Self {
+                #name(value #(, #markers)*)
+            }
+        }
+
+        impl #impl_generics ::core::ops::Deref for #name #ty_generics #where_clause {
+            type Target = #array;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl #impl_generics ::core::ops::DerefMut for #name #ty_generics #where_clause {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl #impl_generics ::ffimage::Pixel for #name #ty_generics #where_clause {
+            const CHANNELS: u8 = (#len) as u8;
+            #subpixels
+        }
+
+        #accessors
+
+        #transparent
+    })
+}
@@ -0,0 +1,79 @@
+use core::marker::PhantomData;
+
+use ffimage::{cast, color::Rgb, iter::*};
+use ffimage_derive::Pixel;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Pixel)]
+#[repr(transparent)]
+#[pixel(channels(y0 = Y0, y1 = Y1, u = U, v = V), subpixels = 2)]
+struct Yuv422<T, const Y0: usize = 0, const Y1: usize = 2, const U: usize = 1, const V: usize = 3>(
+    [T; 4],
+);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Pixel)]
+#[pixel(channels(l, a))]
+struct Tagged<T, W>([T; 2], PhantomData<W>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Pixel)]
+struct Mono([u16; 1]);
+
+impl From<Mono> for Rgb<u16> {
+    fn from(mono: Mono) -> Self {
+        Rgb([mono[0], mono[0], mono[0]])
+    }
+}
+
+fn channels<P: ffimage::Pixel>() -> (u8, u8) {
+    (P::CHANNELS, P::SUBPIXELS)
+}
+
+#[test]
+fn pixel() {
+    assert_eq!(channels::<Yuv422<u8>>(), (4, 2));
+    assert_eq!(channels::<Tagged<f32, ()>>(), (2, 1));
+    assert_eq!(channels::<Mono>(), (1, 1));
+}
+
+#[test]
+fn channels_order() {
+    let mut yuyv = Yuv422::<u8>::from([1, 2, 3, 4]);
+    assert_eq!((*yuyv.y0(), *yuyv.y1(), *yuyv.u(), *yuyv.v()), (1, 3, 2, 4));
+
+    *yuyv.u_mut() = 5;
+    assert_eq!(*yuyv, [1, 5, 3, 4]);
+
+    let uyvy = Yuv422::<u8, 1, 3, 0, 2>::from([1, 2, 3, 4]);
+    assert_eq!((*uyvy.y0(), *uyvy.u()), (2, 1));
+}
+
+#[test]
+fn markers() {
+    let mut pix = Tagged::<f32, ()>::from([0.5, 1.0]);
+    *pix.a_mut() = 0.25;
+
+    assert_eq!(*pix.l(), 0.5);
+    assert_eq!(pix, Tagged([0.5, 0.25], PhantomData));
+}
+
+#[test]
+fn iter() {
+    let buf = [1u16, 2];
+    let rgb: Vec<Rgb<u16>> = buf
+        .iter()
+        .copied()
+        .pixels::<Mono>()
+        .colorconvert::<Rgb<u16>>()
+        .collect();
+
+    assert_eq!(rgb, [Rgb([1, 1, 1]), Rgb([2, 2, 2])]);
+}
+
+#[test]
+fn transparent() {
+    let mut buf = [1u8, 2, 3, 4, 5, 6, 7, 8];
+    let pixels = cast::as_pixels_mut::<Yuv422<u8>>(&mut buf).unwrap();
+    assert_eq!(pixels.len(), 2);
+
+    *pixels[1].v_mut() = 9;
+    assert_eq!(buf, [1, 2, 3, 4, 5, 6, 7, 9]);
+}
@@ -4,7 +4,7 @@ use ffimage::{
     color::Rgb,
     iter::{BytesExt, ColorConvertExt, PixelsExt},
 };
-use ffimage_yuv::{yuv::Yuv, yuv420::Yuv420p, yuv422::Yuv422};
+use ffimage_yuv::{simd, yuv::Yuv, yuv420::Yuv420p, yuv422::Yuv422};
 
 pub fn yuv_to_rgb(c: &mut Criterion) {
     let resolutions = [(640, 480), (1280, 720)];
@@ -23,6 +23,11 @@ pub fn yuv_to_rgb(c: &mut Criterion) {
                     .write(black_box(&mut rgb))
             })
         });
+
+        c.bench_function(
+            &format!("Yuv[u8] -> Rgb[u8] (simd) ({}x{})", res.0, res.1),
+            |b| b.iter(|| simd::yuv_to_rgb(&yuv, black_box(&mut rgb))),
+        );
     }
 }
 
@@ -49,6 +54,11 @@ pub fn yuv422_to_rgb(c: &mut Criterion) {
                 })
             },
         );
+
+        c.bench_function(
+            &format!("Yuv422[u8] -> Rgb[u8] (simd) ({}x{})", res.0, res.1),
+            |b| b.iter(|| simd::yuyv_to_rgb(&yuv422, black_box(&mut rgb))),
+        );
     }
 }
 
@@ -0,0 +1,89 @@
+//! Bulk conversion of whole buffers, including YUV.
+//!
+//! [`YuvKernel`] hooks the kernels of the [`simd`] module into
+//! [`ffimage::convert`]. [`convert`] tries it after the kernels of `ffimage` itself:
+//!
+//! ```
+//! use ffimage::color::Rgb;
+//! use ffimage_yuv::{convert::convert, yuv::Yuv};
+//!
+//! let yuv = [82u8, 90, 240, 145, 54, 34];
+//! let mut rgb = [0u8; 6];
+//! convert::<Yuv<u8>, Rgb<u8>>(&yuv, &mut rgb).unwrap();
+//!
+//! assert_eq!(rgb[..3], Rgb::<u8>::from(Yuv::<u8>([82, 90, 240])).0);
+//! ```
+
+use core::any::TypeId;
+
+use ffimage::{
+    color::Rgb,
+    convert::{Convert, ConvertKernel, SimdKernel},
+    Error,
+};
+
+use crate::{simd, yuv::Yuv};
+
+fn is<A: 'static, B: 'static>() -> bool {
+    TypeId::of::<A>() == TypeId::of::<B>()
+}
+
+/// Kernels of the [`simd`] module
+///
+/// Handles `Yuv<u8>` to `Rgb<u8>` and back.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct YuvKernel;
+
+impl ConvertKernel for YuvKernel {
+    fn convert<S: 'static, D: 'static>(src: &[u8], dst: &mut [u8]) -> bool {
+        if is::<S, Yuv<u8>>() && is::<D, Rgb<u8>>() {
+            simd::yuv_to_rgb(src, dst);
+        } else if is::<S, Rgb<u8>>() && is::<D, Yuv<u8>>() {
+            simd::rgb_to_yuv(src, dst);
+        } else {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// Converts the pixels of `src` and writes them to `dst`.
+///
+/// Like [`ffimage::convert::convert`], but tries the [`YuvKernel`] as well.
+pub fn convert<S, D>(src: &[S::Src], dst: &mut [S::Dst]) -> Result<(), Error>
+where
+    S: Convert<D>,
+{
+    S::convert_with::<(SimdKernel, YuvKernel)>(src, dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ffimage::color::Bgr;
+
+    #[test]
+    fn kernel() {
+        let yuv: [u8; 3 * 70] = core::array::from_fn(|i| (i * 37 + i / 5) as u8);
+
+        // the kernels against the From implementations
+        let mut rgb = [0u8; 3 * 70];
+        let mut scalar = [0u8; 3 * 70];
+        convert::<Yuv<u8>, Rgb<u8>>(&yuv, &mut rgb).unwrap();
+        ffimage::convert::convert_with::<Yuv<u8>, Rgb<u8>, ()>(&yuv, &mut scalar).unwrap();
+        assert_eq!(rgb, scalar);
+
+        let mut out = [0u8; 3 * 70];
+        convert::<Rgb<u8>, Yuv<u8>>(&rgb, &mut out).unwrap();
+        ffimage::convert::convert_with::<Rgb<u8>, Yuv<u8>, ()>(&rgb, &mut scalar).unwrap();
+        assert_eq!(out, scalar);
+
+        // the kernels of ffimage are still used
+        let mut bgr = [0u8; 3 * 70];
+        convert::<Rgb<u8>, Bgr<u8>>(&rgb, &mut bgr).unwrap();
+        for (rgb, bgr) in rgb.chunks_exact(3).zip(bgr.chunks_exact(3)) {
+            assert_eq!(rgb, [bgr[2], bgr[1], bgr[0]]);
+        }
+    }
+}
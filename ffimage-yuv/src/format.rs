@@ -0,0 +1,392 @@
+//! Dynamic conversion of all pixel formats, including YUV.
+//!
+//! [`convert`] and [`convert_image`] extend [`ffimage::format::convert`] and
+//! [`ffimage::format::convert_image`] by the YUV formats of [`PixelFormat`].
+//! Conversions from YUV to RGB use the kernels of the [`simd`] module, all other
+//! pairs of formats are converted through the typed `From` implementations:
+//!
+//! ```
+//! use ffimage::format::PixelFormat;
+//! use ffimage_yuv::format::convert;
+//!
+//! let yuyv = [82u8, 90, 82, 240, 145, 54, 145, 34];
+//! let mut rgba = [0u8; 16];
+//! convert(PixelFormat::Yuyv, &yuyv, PixelFormat::Rgba32, &mut rgba, 4, 1).unwrap();
+//!
+//! // a red pixel
+//! assert_eq!(rgba[..4], [255, 1, 0, 255]);
+//! ```
+
+use ffimage::{
+    color::Rgb,
+    format::{self, check_len, Hub, PixelFormat},
+    image::{DynamicImageView, DynamicImageViewMut},
+    sample::FromDepth,
+    Error,
+};
+
+use crate::{
+    simd,
+    yuv::Yuv,
+    yuv422::{Uyvy, Yuyv},
+};
+
+/// Number of pixels per row converted at once
+const CHUNK: usize = 64;
+
+fn is_yuv(fmt: PixelFormat) -> bool {
+    matches!(
+        fmt,
+        PixelFormat::Yuv444 | PixelFormat::Yuyv | PixelFormat::Uyvy | PixelFormat::Yuv420p
+    )
+}
+
+fn to_hub(yuv: Yuv<u8>) -> Hub {
+    Hub::from(Rgb::<u16>::from_depth(Rgb::<u8>::from(yuv)))
+}
+
+fn from_hub(hub: Hub) -> Yuv<u8> {
+    Yuv::from(Rgb::<u8>::from_depth(Rgb::<u16>::from(hub)))
+}
+
+/// Converts a frame from one pixel format to another.
+///
+/// Fails if a buffer does not hold a tightly packed frame of `width` x `height` pixels in its
+/// format or if the dimensions do not fit the chroma subsampling of a format. Use
+/// [`convert_image`] for frames with padded rows.
+pub fn convert(
+    src_fmt: PixelFormat,
+    src: &[u8],
+    dst_fmt: PixelFormat,
+    dst: &mut [u8],
+    width: u32,
+    height: u32,
+) -> Result<(), Error> {
+    if !is_yuv(src_fmt) && !is_yuv(dst_fmt) {
+        return format::convert(src_fmt, src, dst_fmt, dst, width, height);
+    }
+    check_len(src_fmt, src.len(), width, height)?;
+    check_len(dst_fmt, dst.len(), width, height)?;
+
+    let src =
+        DynamicImageView::new(src, src_fmt, width, height).expect("frame length was validated");
+    let mut dst =
+        DynamicImageViewMut::new(dst, dst_fmt, width, height).expect("frame length was validated");
+    convert_image(&src, &mut dst)
+}
+
+/// Converts an image from one pixel format to another.
+///
+/// Like [`convert`], but honors the stride of both images. Fails with
+/// [`Error::InvalidDimensions`] if the images differ in size.
+///
+/// # Arguments
+///
+/// * `src` - Source image
+/// * `dst` - Destination image
+pub fn convert_image(src: &DynamicImageView, dst: &mut DynamicImageViewMut) -> Result<(), Error> {
+    let (src_fmt, dst_fmt) = (src.format(), dst.format());
+    if !is_yuv(src_fmt) && !is_yuv(dst_fmt) {
+        return format::convert_image(src, dst);
+    }
+    if src.width() != dst.width() || src.height() != dst.height() {
+        return Err(Error::InvalidDimensions {
+            width: dst.width(),
+            height: dst.height(),
+        });
+    }
+
+    let (width, height) = (src.width(), src.height());
+    // the kernels of the SIMD module work on whole rows
+    let rows = |dst: &mut DynamicImageViewMut, f: fn(&[u8], &mut [u8])| {
+        for (src, dst) in src.rows().zip(dst.rows_mut()) {
+            f(src, dst)
+        }
+    };
+
+    match (src_fmt, dst_fmt) {
+        // planar formats are always tightly packed
+        _ if src_fmt == dst_fmt && src_fmt.planes() > 1 => {
+            let len = src_fmt.frame_len(width, height)?;
+            dst.as_mut_slice()[..len].copy_from_slice(&src.as_slice()[..len])
+        }
+        _ if src_fmt == dst_fmt => rows(dst, |src, dst| dst.copy_from_slice(src)),
+        (PixelFormat::Yuv444, PixelFormat::Rgb24) => rows(dst, simd::yuv_to_rgb),
+        (PixelFormat::Rgb24, PixelFormat::Yuv444) => rows(dst, simd::rgb_to_yuv),
+        (PixelFormat::Yuyv, PixelFormat::Rgb24) => rows(dst, simd::yuyv_to_rgb),
+        (PixelFormat::Uyvy, PixelFormat::Rgb24) => rows(dst, simd::uyvy_to_rgb),
+        (PixelFormat::Yuv420p, PixelFormat::Rgb24) if dst.stride() == width as usize * 3 => {
+            let src = &src.as_slice()[..src_fmt.frame_len(width, height)?];
+            let dst = &mut dst.as_mut_slice()[..dst_fmt.frame_len(width, height)?];
+            let (y, uv) = src.split_at(width as usize * height as usize);
+            let (u, v) = uv.split_at(uv.len() / 2);
+            simd::yuv420p_to_rgb(y, u, v, width, height, dst)
+        }
+        _ => {
+            let (w, h) = (width as usize, height as usize);
+            let mut hub = [[Hub::default(); CHUNK]; 2];
+
+            // pairs of rows keep 4:2:0 chroma blocks together
+            for y in (0..h).step_by(2) {
+                let rows = (h - y).min(2);
+                for x in (0..w).step_by(CHUNK) {
+                    let n = (w - x).min(CHUNK);
+                    for (row, hub) in hub.iter_mut().take(rows).enumerate() {
+                        decode(src, y + row, x, &mut hub[..n])?;
+                    }
+                    encode(&hub[..rows], n, dst, y, x)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Decodes the pixels of a row starting at column `x` into `dst`.
+fn decode(src: &DynamicImageView, y: usize, x: usize, dst: &mut [Hub]) -> Result<(), Error> {
+    let fmt = src.format();
+    let bytes = fmt.bits_per_pixel() as usize / 8;
+    let (width, height, stride) = (src.width() as usize, src.height() as usize, src.stride());
+    let src = src.as_slice();
+
+    match fmt {
+        PixelFormat::Yuv444 => {
+            for (src, dst) in src[y * stride + x * 3..].chunks_exact(3).zip(dst) {
+                *dst = to_hub(Yuv([src[0], src[1], src[2]]));
+            }
+        }
+        PixelFormat::Yuyv | PixelFormat::Uyvy => {
+            for (src, dst) in src[y * stride + x * 2..]
+                .chunks_exact(4)
+                .zip(dst.chunks_exact_mut(2))
+            {
+                let src = [src[0], src[1], src[2], src[3]];
+                let pix = if fmt == PixelFormat::Yuyv {
+                    <[Yuv<u8>; 2]>::from(Yuyv::from(src))
+                } else {
+                    <[Yuv<u8>; 2]>::from(Uyvy::from(src))
+                };
+                dst[0] = to_hub(pix[0]);
+                dst[1] = to_hub(pix[1]);
+            }
+        }
+        PixelFormat::Yuv420p => {
+            let (luma, chroma) = src.split_at(width * height);
+            let (u, v) = chroma.split_at(width * height / 4);
+            let offset = y * width + x;
+            let chroma = y / 2 * width / 2;
+
+            for (i, dst) in dst.iter_mut().enumerate() {
+                let c = chroma + (x + i) / 2;
+                *dst = to_hub(Yuv([luma[offset + i], u[c], v[c]]));
+            }
+        }
+        _ => {
+            let offset = y * stride + x * bytes;
+            format::decode(fmt, &src[offset..offset + dst.len() * bytes], dst)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Encodes the first `n` pixels of up to two rows starting at row `y` and column `x`.
+fn encode(
+    src: &[[Hub; CHUNK]],
+    n: usize,
+    dst: &mut DynamicImageViewMut,
+    y: usize,
+    x: usize,
+) -> Result<(), Error> {
+    let fmt = dst.format();
+    let bytes = fmt.bits_per_pixel() as usize / 8;
+    let (width, height, stride) = (dst.width() as usize, dst.height() as usize, dst.stride());
+    let dst = dst.as_mut_slice();
+
+    if fmt == PixelFormat::Yuv420p {
+        let (luma, chroma) = dst.split_at_mut(width * height);
+        let (u, v) = chroma.split_at_mut(width * height / 4);
+        let chroma = y / 2 * width / 2 + x / 2;
+
+        // chroma is averaged over blocks of 2x2 pixels
+        for i in (0..n).step_by(2) {
+            let mut sum = [0u32; 2];
+            for (row, src) in src.iter().enumerate() {
+                for j in i..i + 2 {
+                    let yuv = from_hub(src[j]);
+                    luma[(y + row) * width + x + j] = yuv[0];
+                    sum[0] += yuv[1] as u32;
+                    sum[1] += yuv[2] as u32;
+                }
+            }
+            u[chroma + i / 2] = ((sum[0] + 2) / 4) as u8;
+            v[chroma + i / 2] = ((sum[1] + 2) / 4) as u8;
+        }
+
+        return Ok(());
+    }
+
+    for (row, src) in src.iter().enumerate() {
+        let offset = (y + row) * stride + x * bytes;
+        let src = &src[..n];
+
+        match fmt {
+            PixelFormat::Yuv444 => {
+                for (src, dst) in src.iter().zip(dst[offset..].chunks_exact_mut(3)) {
+                    dst.copy_from_slice(&*from_hub(*src));
+                }
+            }
+            PixelFormat::Yuyv | PixelFormat::Uyvy => {
+                for (src, dst) in src.chunks_exact(2).zip(dst[offset..].chunks_exact_mut(4)) {
+                    let pix = [from_hub(src[0]), from_hub(src[1])];
+                    if fmt == PixelFormat::Yuyv {
+                        dst.copy_from_slice(&*Yuyv::from(pix));
+                    } else {
+                        dst.copy_from_slice(&*Uyvy::from(pix));
+                    }
+                }
+            }
+            _ => format::encode(fmt, src, &mut dst[offset..offset + n * bytes])?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::{vec, vec::Vec};
+
+    fn frame(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + i / 13) as u8).collect()
+    }
+
+    #[test]
+    fn typed() {
+        let (w, h) = (70u32, 4u32);
+
+        for &fmt in &[
+            PixelFormat::Yuv444,
+            PixelFormat::Yuyv,
+            PixelFormat::Uyvy,
+            PixelFormat::Yuv420p,
+        ] {
+            let src = frame(fmt.frame_len(w, h).unwrap());
+
+            // the SIMD kernels against the generic path
+            let mut rgb = vec![0; PixelFormat::Rgb24.frame_len(w, h).unwrap()];
+            let mut bgr = vec![0; rgb.len()];
+            convert(fmt, &src, PixelFormat::Rgb24, &mut rgb, w, h).unwrap();
+            convert(fmt, &src, PixelFormat::Bgr24, &mut bgr, w, h).unwrap();
+            for (rgb, bgr) in rgb.chunks_exact(3).zip(bgr.chunks_exact(3)) {
+                assert_eq!(rgb, [bgr[2], bgr[1], bgr[0]], "{:?}", fmt);
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let (w, h) = (66u32, 6u32);
+        // gray pixels survive the chroma subsampling
+        let gray: Vec<u8> = frame((w * h) as usize)
+            .iter()
+            .map(|&v| v / 2 + 64)
+            .collect();
+        let mut rgb = vec![0; (w * h * 3) as usize];
+        convert(
+            PixelFormat::Gray8,
+            &gray,
+            PixelFormat::Rgb24,
+            &mut rgb,
+            w,
+            h,
+        )
+        .unwrap();
+
+        for &fmt in &[
+            PixelFormat::Yuv444,
+            PixelFormat::Yuyv,
+            PixelFormat::Uyvy,
+            PixelFormat::Yuv420p,
+        ] {
+            let mut yuv = vec![0; fmt.frame_len(w, h).unwrap()];
+            let mut out = vec![0; gray.len()];
+            convert(PixelFormat::Rgb24, &rgb, fmt, &mut yuv, w, h).unwrap();
+            convert(fmt, &yuv, PixelFormat::Gray8, &mut out, w, h).unwrap();
+
+            for (a, b) in gray.iter().zip(&out) {
+                assert!((*a as i32 - *b as i32).abs() <= 2, "{:?}", fmt);
+            }
+        }
+    }
+
+    #[test]
+    fn all() {
+        for &src_fmt in PixelFormat::ALL {
+            for &dst_fmt in PixelFormat::ALL {
+                let src = frame(src_fmt.frame_len(6, 4).unwrap());
+                let mut dst = vec![0; dst_fmt.frame_len(6, 4).unwrap()];
+                assert_eq!(convert(src_fmt, &src, dst_fmt, &mut dst, 6, 4), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn stride() {
+        let (w, h) = (6u32, 4u32);
+
+        for &src_fmt in PixelFormat::ALL {
+            for &dst_fmt in PixelFormat::ALL {
+                if !is_yuv(src_fmt) && !is_yuv(dst_fmt) {
+                    continue;
+                }
+                let src = frame(src_fmt.frame_len(w, h).unwrap());
+                let mut tight = vec![0; dst_fmt.frame_len(w, h).unwrap()];
+                convert(src_fmt, &src, dst_fmt, &mut tight, w, h).unwrap();
+
+                // pads the rows of packed formats by five bytes
+                let pad = |fmt: PixelFormat, buf: &[u8]| match fmt.row_len(w) {
+                    Some(row_len) => (
+                        buf.chunks(row_len)
+                            .flat_map(|row| row.iter().copied().chain([0xaa; 5]))
+                            .collect(),
+                        row_len + 5,
+                    ),
+                    None => (buf.to_vec(), w as usize),
+                };
+                let (src, src_stride) = pad(src_fmt, &src);
+                let (expected, dst_stride) = pad(dst_fmt, &tight);
+                let mut dst = vec![0xaa; expected.len()];
+
+                let src = DynamicImageView::with_stride(&src, src_fmt, w, h, src_stride).unwrap();
+                let mut view =
+                    DynamicImageViewMut::with_stride(&mut dst, dst_fmt, w, h, dst_stride).unwrap();
+                convert_image(&src, &mut view).unwrap();
+                assert_eq!(dst, expected, "{:?} {:?}", src_fmt, dst_fmt);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_dimensions() {
+        let mut dst = [0; 9];
+        assert_eq!(
+            convert(
+                PixelFormat::Yuv420p,
+                &[0; 6],
+                PixelFormat::Rgb24,
+                &mut dst,
+                3,
+                1
+            ),
+            Err(Error::InvalidDimensions {
+                width: 3,
+                height: 1
+            })
+        );
+    }
+}
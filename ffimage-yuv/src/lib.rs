@@ -1,5 +1,10 @@
 #![no_std]
 
+pub mod convert;
+pub mod format;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod simd;
 pub mod yuv;
 pub mod yuv420;
 pub mod yuv422;
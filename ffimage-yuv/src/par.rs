@@ -0,0 +1,154 @@
+//! Parallel YUV frame conversion.
+//!
+//! Like [`ffimage::par`], frames are split into bands of rows which are converted in parallel
+//! by the serial kernels of the [`simd`](crate::simd) module, so the output is identical to the
+//! serial path.
+
+use ffimage::par::bands;
+
+use crate::simd;
+
+/// Converts a frame of packed YUV 4:4:4 to RGB pixels.
+///
+/// # Panics
+///
+/// Panics if `src` or `dst` do not hold `width * height` pixels.
+pub fn yuv_to_rgb(src: &[u8], width: u32, height: u32, dst: &mut [u8]) {
+    let stride = width as usize * 3;
+    bands(
+        src,
+        stride,
+        dst,
+        stride,
+        height as usize,
+        1,
+        |src, dst, _| simd::yuv_to_rgb(src, dst),
+    )
+}
+
+/// Converts a frame of RGB to packed YUV 4:4:4 pixels.
+///
+/// # Panics
+///
+/// Panics if `src` or `dst` do not hold `width * height` pixels.
+pub fn rgb_to_yuv(src: &[u8], width: u32, height: u32, dst: &mut [u8]) {
+    let stride = width as usize * 3;
+    bands(
+        src,
+        stride,
+        dst,
+        stride,
+        height as usize,
+        1,
+        |src, dst, _| simd::rgb_to_yuv(src, dst),
+    )
+}
+
+/// Converts a frame of YUYV (4:2:2) to RGB pixels.
+///
+/// # Panics
+///
+/// Panics if `width` is odd or if `src` or `dst` do not hold `width * height` pixels.
+pub fn yuyv_to_rgb(src: &[u8], width: u32, height: u32, dst: &mut [u8]) {
+    assert_eq!(width % 2, 0);
+    let width = width as usize;
+    bands(
+        src,
+        width * 2,
+        dst,
+        width * 3,
+        height as usize,
+        1,
+        |src, dst, _| simd::yuyv_to_rgb(src, dst),
+    )
+}
+
+/// Converts a frame of UYVY (4:2:2) to RGB pixels.
+///
+/// # Panics
+///
+/// Panics if `width` is odd or if `src` or `dst` do not hold `width * height` pixels.
+pub fn uyvy_to_rgb(src: &[u8], width: u32, height: u32, dst: &mut [u8]) {
+    assert_eq!(width % 2, 0);
+    let width = width as usize;
+    bands(
+        src,
+        width * 2,
+        dst,
+        width * 3,
+        height as usize,
+        1,
+        |src, dst, _| simd::uyvy_to_rgb(src, dst),
+    )
+}
+
+/// Converts a frame of YUV 4:2:0 planes to RGB pixels.
+///
+/// # Panics
+///
+/// Panics if `width` or `height` is odd or if the planes or `dst` do not match the dimensions.
+pub fn yuv420p_to_rgb(y: &[u8], u: &[u8], v: &[u8], width: u32, height: u32, dst: &mut [u8]) {
+    let (w, h) = (width as usize, height as usize);
+    assert_eq!(w % 2, 0);
+    assert_eq!(h % 2, 0);
+    assert_eq!(u.len(), w * h / 4);
+    assert_eq!(v.len(), w * h / 4);
+
+    // bands have an even number of rows, so each one covers whole chroma rows
+    bands(y, w, dst, w * 3, h, 2, |y, dst, rows| {
+        let chroma = rows.start / 2 * w / 2..rows.end / 2 * w / 2;
+        let rows = rows.len() as u32;
+        simd::yuv420p_to_rgb(y, &u[chroma.clone()], &v[chroma], width, rows, dst)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::{vec, vec::Vec};
+
+    fn frame(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + i / 13) as u8).collect()
+    }
+
+    #[test]
+    fn packed() {
+        let (w, h) = (66, 37);
+        let src = frame(w * h * 3);
+        let mut serial = vec![0; w * h * 3];
+        let mut parallel = vec![0; w * h * 3];
+
+        simd::yuv_to_rgb(&src, &mut serial);
+        super::yuv_to_rgb(&src, w as u32, h as u32, &mut parallel);
+        assert_eq!(serial, parallel);
+
+        simd::rgb_to_yuv(&src, &mut serial);
+        super::rgb_to_yuv(&src, w as u32, h as u32, &mut parallel);
+        assert_eq!(serial, parallel);
+
+        let src = frame(w * h * 2);
+        simd::yuyv_to_rgb(&src, &mut serial);
+        super::yuyv_to_rgb(&src, w as u32, h as u32, &mut parallel);
+        assert_eq!(serial, parallel);
+
+        simd::uyvy_to_rgb(&src, &mut serial);
+        super::uyvy_to_rgb(&src, w as u32, h as u32, &mut parallel);
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn planar() {
+        let (w, h) = (66, 38);
+        let y = frame(w * h);
+        let u = frame(w * h / 4);
+        let v: Vec<u8> = u.iter().rev().copied().collect();
+        let mut serial = vec![0; w * h * 3];
+        let mut parallel = vec![0; w * h * 3];
+
+        simd::yuv420p_to_rgb(&y, &u, &v, w as u32, h as u32, &mut serial);
+        super::yuv420p_to_rgb(&y, &u, &v, w as u32, h as u32, &mut parallel);
+        assert_eq!(serial, parallel);
+    }
+}
@@ -0,0 +1,159 @@
+//! aarch64 kernels.
+
+use core::arch::aarch64::*;
+
+/// Returns the 32 bit sums shifted right by 8 bits as 16 bit numbers.
+#[inline(always)]
+unsafe fn narrow(lo: int32x4_t, hi: int32x4_t) -> int16x8_t {
+    vcombine_s16(
+        vqmovn_s32(vshrq_n_s32(lo, 8)),
+        vqmovn_s32(vshrq_n_s32(hi, 8)),
+    )
+}
+
+/// Returns `a * ka + b * kb + c * kc + round` of eight 16 bit samples, shifted right by 8 bits.
+#[inline(always)]
+unsafe fn dot(a: int16x8_t, b: int16x8_t, c: int16x8_t, k: [i16; 3], round: i32) -> int16x8_t {
+    let round = vdupq_n_s32(round);
+    let lo = vmlal_n_s16(round, vget_low_s16(a), k[0]);
+    let lo = vmlal_n_s16(lo, vget_low_s16(b), k[1]);
+    let lo = vmlal_n_s16(lo, vget_low_s16(c), k[2]);
+    let hi = vmlal_n_s16(round, vget_high_s16(a), k[0]);
+    let hi = vmlal_n_s16(hi, vget_high_s16(b), k[1]);
+    let hi = vmlal_n_s16(hi, vget_high_s16(c), k[2]);
+
+    narrow(lo, hi)
+}
+
+#[inline(always)]
+unsafe fn widen(v: uint8x8_t) -> int16x8_t {
+    vreinterpretq_s16_u16(vmovl_u8(v))
+}
+
+#[inline(always)]
+unsafe fn yuv_to_rgb8(y: uint8x8_t, u: uint8x8_t, v: uint8x8_t) -> [int16x8_t; 3] {
+    let c = vsubq_s16(widen(y), vdupq_n_s16(16));
+    let d = vsubq_s16(widen(u), vdupq_n_s16(128));
+    let e = vsubq_s16(widen(v), vdupq_n_s16(128));
+
+    [
+        dot(c, d, e, [298, 0, 409], 128),
+        dot(c, d, e, [298, -100, -208], 128),
+        dot(c, d, e, [298, 516, 0], 128),
+    ]
+}
+
+/// Converts 16 YUV pixels to RGB.
+#[inline(always)]
+unsafe fn yuv_to_rgb16(y: uint8x16_t, u: uint8x16_t, v: uint8x16_t) -> uint8x16x3_t {
+    let lo = yuv_to_rgb8(vget_low_u8(y), vget_low_u8(u), vget_low_u8(v));
+    let hi = yuv_to_rgb8(vget_high_u8(y), vget_high_u8(u), vget_high_u8(v));
+
+    // saturates to 0..255, which is the clamping of the scalar code
+    uint8x16x3_t(
+        vcombine_u8(vqmovun_s16(lo[0]), vqmovun_s16(hi[0])),
+        vcombine_u8(vqmovun_s16(lo[1]), vqmovun_s16(hi[1])),
+        vcombine_u8(vqmovun_s16(lo[2]), vqmovun_s16(hi[2])),
+    )
+}
+
+#[inline(always)]
+unsafe fn rgb_to_yuv8(r: uint8x8_t, g: uint8x8_t, b: uint8x8_t) -> [uint8x8_t; 3] {
+    let (r, g, b) = (widen(r), widen(g), widen(b));
+    let y = dot(r, g, b, [66, 129, 25], 128);
+    let u = dot(r, g, b, [-38, -74, 112], 128);
+    let v = dot(r, g, b, [112, -94, -18], 128);
+
+    [
+        vqmovun_s16(vaddq_s16(y, vdupq_n_s16(16))),
+        vqmovun_s16(vaddq_s16(u, vdupq_n_s16(128))),
+        vqmovun_s16(vaddq_s16(v, vdupq_n_s16(128))),
+    ]
+}
+
+/// Returns the number of converted pixels.
+pub(crate) unsafe fn yuv_to_rgb_neon(src: &[u8], dst: &mut [u8]) -> usize {
+    let mut n = 0;
+    while (n + 16) * 3 <= src.len() {
+        let v = vld3q_u8(src.as_ptr().add(n * 3));
+        vst3q_u8(dst.as_mut_ptr().add(n * 3), yuv_to_rgb16(v.0, v.1, v.2));
+        n += 16;
+    }
+
+    n
+}
+
+/// Returns the number of converted pixels.
+pub(crate) unsafe fn rgb_to_yuv_neon(src: &[u8], dst: &mut [u8]) -> usize {
+    let mut n = 0;
+    while (n + 16) * 3 <= src.len() {
+        let v = vld3q_u8(src.as_ptr().add(n * 3));
+        let lo = rgb_to_yuv8(vget_low_u8(v.0), vget_low_u8(v.1), vget_low_u8(v.2));
+        let hi = rgb_to_yuv8(vget_high_u8(v.0), vget_high_u8(v.1), vget_high_u8(v.2));
+        vst3q_u8(
+            dst.as_mut_ptr().add(n * 3),
+            uint8x16x3_t(
+                vcombine_u8(lo[0], hi[0]),
+                vcombine_u8(lo[1], hi[1]),
+                vcombine_u8(lo[2], hi[2]),
+            ),
+        );
+        n += 16;
+    }
+
+    n
+}
+
+/// Converts packed 4:2:2 pixels, `Y_ODD` tells whether luma is stored at odd (UYVY) or even
+/// (YUYV) positions.
+#[inline(always)]
+unsafe fn yuv422_to_rgb<const Y_ODD: bool>(src: &[u8], dst: &mut [u8]) -> usize {
+    let mut n = 0;
+    while (n + 32) * 2 <= src.len() {
+        let v = vld4q_u8(src.as_ptr().add(n * 2));
+        let (y0, u, y1, v) = if Y_ODD {
+            (v.1, v.0, v.3, v.2)
+        } else {
+            (v.0, v.1, v.2, v.3)
+        };
+
+        // each chroma sample is shared by two pixels
+        let rgb = yuv_to_rgb16(vzip1q_u8(y0, y1), vzip1q_u8(u, u), vzip1q_u8(v, v));
+        vst3q_u8(dst.as_mut_ptr().add(n * 3), rgb);
+        let rgb = yuv_to_rgb16(vzip2q_u8(y0, y1), vzip2q_u8(u, u), vzip2q_u8(v, v));
+        vst3q_u8(dst.as_mut_ptr().add(n * 3 + 48), rgb);
+        n += 32;
+    }
+
+    n
+}
+
+/// Returns the number of converted pixels.
+pub(crate) unsafe fn yuyv_to_rgb_neon(src: &[u8], dst: &mut [u8]) -> usize {
+    yuv422_to_rgb::<false>(src, dst)
+}
+
+/// Returns the number of converted pixels.
+pub(crate) unsafe fn uyvy_to_rgb_neon(src: &[u8], dst: &mut [u8]) -> usize {
+    yuv422_to_rgb::<true>(src, dst)
+}
+
+/// Converts a row of 4:2:0 planar pixels, `u` and `v` hold the chroma samples of the row.
+///
+/// Returns the number of converted pixels.
+pub(crate) unsafe fn yuv420p_to_rgb_neon(y: &[u8], u: &[u8], v: &[u8], dst: &mut [u8]) -> usize {
+    let mut n = 0;
+    while n + 16 <= y.len() {
+        let cu = vcombine_u8(vld1_u8(u.as_ptr().add(n / 2)), vdup_n_u8(0));
+        let cv = vcombine_u8(vld1_u8(v.as_ptr().add(n / 2)), vdup_n_u8(0));
+        let rgb = yuv_to_rgb16(
+            vld1q_u8(y.as_ptr().add(n)),
+            vzip1q_u8(cu, cu),
+            vzip1q_u8(cv, cv),
+        );
+        vst3q_u8(dst.as_mut_ptr().add(n * 3), rgb);
+        n += 16;
+    }
+
+    n
+}
@@ -0,0 +1,311 @@
+//! SIMD accelerated YUV conversion kernels.
+//!
+//! Like the kernels in [`ffimage::simd`], these operate on whole byte slices of 8 bit pixels,
+//! pick the best instruction set supported by the CPU at runtime and produce results which are
+//! bit-identical to the scalar `From` implementations:
+//!
+//! ```
+//! use ffimage::color::Rgb;
+//! use ffimage_yuv::{simd, yuv::Yuv};
+//!
+//! let yuyv = [[82u8, 90, 82, 240]; 50].concat();
+//! let mut rgb = [0u8; 300];
+//! simd::yuyv_to_rgb(&yuyv, &mut rgb);
+//!
+//! assert_eq!(rgb[297..], Rgb::<u8>::from(Yuv::<u8>([82, 90, 240])).0);
+//! ```
+
+use ffimage::{color::Rgb, simd::Isa};
+
+use crate::{
+    yuv::Yuv,
+    yuv422::{Uyvy, Yuyv},
+};
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+#[cfg(target_arch = "x86_64")]
+mod x86;
+
+/// Converts packed YUV 4:4:4 to RGB pixels.
+///
+/// # Panics
+///
+/// Panics if `src` does not contain whole pixels or if `dst` has a different length.
+pub fn yuv_to_rgb(src: &[u8], dst: &mut [u8]) {
+    yuv_to_rgb_with(Isa::detect(), src, dst)
+}
+
+fn yuv_to_rgb_with(isa: Isa, src: &[u8], dst: &mut [u8]) {
+    assert_eq!(src.len() % 3, 0);
+    assert_eq!(src.len(), dst.len());
+
+    let n = match isa {
+        #[cfg(target_arch = "x86_64")]
+        Isa::Avx2 => unsafe { x86::yuv_to_rgb_avx2(src, dst) },
+        #[cfg(target_arch = "x86_64")]
+        Isa::Sse2 | Isa::Ssse3 => unsafe { x86::yuv_to_rgb_sse2(src, dst) },
+        #[cfg(target_arch = "aarch64")]
+        Isa::Neon => unsafe { aarch64::yuv_to_rgb_neon(src, dst) },
+        _ => 0,
+    };
+
+    for (src, dst) in src[n * 3..]
+        .chunks_exact(3)
+        .zip(dst[n * 3..].chunks_exact_mut(3))
+    {
+        let rgb = Rgb::<u8>::from(Yuv::<u8>([src[0], src[1], src[2]]));
+        dst.copy_from_slice(&rgb.0);
+    }
+}
+
+/// Converts RGB to packed YUV 4:4:4 pixels.
+///
+/// # Panics
+///
+/// Panics if `src` does not contain whole pixels or if `dst` has a different length.
+pub fn rgb_to_yuv(src: &[u8], dst: &mut [u8]) {
+    rgb_to_yuv_with(Isa::detect(), src, dst)
+}
+
+fn rgb_to_yuv_with(isa: Isa, src: &[u8], dst: &mut [u8]) {
+    assert_eq!(src.len() % 3, 0);
+    assert_eq!(src.len(), dst.len());
+
+    let n = match isa {
+        #[cfg(target_arch = "x86_64")]
+        Isa::Avx2 => unsafe { x86::rgb_to_yuv_avx2(src, dst) },
+        #[cfg(target_arch = "x86_64")]
+        Isa::Sse2 | Isa::Ssse3 => unsafe { x86::rgb_to_yuv_sse2(src, dst) },
+        #[cfg(target_arch = "aarch64")]
+        Isa::Neon => unsafe { aarch64::rgb_to_yuv_neon(src, dst) },
+        _ => 0,
+    };
+
+    for (src, dst) in src[n * 3..]
+        .chunks_exact(3)
+        .zip(dst[n * 3..].chunks_exact_mut(3))
+    {
+        let yuv = Yuv::<u8>::from(Rgb::<u8>([src[0], src[1], src[2]]));
+        dst.copy_from_slice(&yuv.0);
+    }
+}
+
+/// Converts YUYV (4:2:2) to RGB pixels.
+///
+/// # Panics
+///
+/// Panics if `src` does not contain whole macropixels or if `dst` does not hold the RGB
+/// samples of exactly as many pixels.
+pub fn yuyv_to_rgb(src: &[u8], dst: &mut [u8]) {
+    yuyv_to_rgb_with(Isa::detect(), src, dst)
+}
+
+fn yuyv_to_rgb_with(isa: Isa, src: &[u8], dst: &mut [u8]) {
+    assert_eq!(src.len() % 4, 0);
+    assert_eq!(src.len() / 2 * 3, dst.len());
+
+    let n = match isa {
+        #[cfg(target_arch = "x86_64")]
+        Isa::Avx2 => unsafe { x86::yuyv_to_rgb_avx2(src, dst) },
+        #[cfg(target_arch = "x86_64")]
+        Isa::Sse2 | Isa::Ssse3 => unsafe { x86::yuyv_to_rgb_sse2(src, dst) },
+        #[cfg(target_arch = "aarch64")]
+        Isa::Neon => unsafe { aarch64::yuyv_to_rgb_neon(src, dst) },
+        _ => 0,
+    };
+
+    for (src, dst) in src[n * 2..]
+        .chunks_exact(4)
+        .zip(dst[n * 3..].chunks_exact_mut(6))
+    {
+        let yuv = <[Yuv<u8>; 2]>::from(Yuyv::<u8>::from([src[0], src[1], src[2], src[3]]));
+        dst[..3].copy_from_slice(&Rgb::<u8>::from(yuv[0]).0);
+        dst[3..].copy_from_slice(&Rgb::<u8>::from(yuv[1]).0);
+    }
+}
+
+/// Converts UYVY (4:2:2) to RGB pixels.
+///
+/// # Panics
+///
+/// Panics if `src` does not contain whole macropixels or if `dst` does not hold the RGB
+/// samples of exactly as many pixels.
+pub fn uyvy_to_rgb(src: &[u8], dst: &mut [u8]) {
+    uyvy_to_rgb_with(Isa::detect(), src, dst)
+}
+
+fn uyvy_to_rgb_with(isa: Isa, src: &[u8], dst: &mut [u8]) {
+    assert_eq!(src.len() % 4, 0);
+    assert_eq!(src.len() / 2 * 3, dst.len());
+
+    let n = match isa {
+        #[cfg(target_arch = "x86_64")]
+        Isa::Avx2 => unsafe { x86::uyvy_to_rgb_avx2(src, dst) },
+        #[cfg(target_arch = "x86_64")]
+        Isa::Sse2 | Isa::Ssse3 => unsafe { x86::uyvy_to_rgb_sse2(src, dst) },
+        #[cfg(target_arch = "aarch64")]
+        Isa::Neon => unsafe { aarch64::uyvy_to_rgb_neon(src, dst) },
+        _ => 0,
+    };
+
+    for (src, dst) in src[n * 2..]
+        .chunks_exact(4)
+        .zip(dst[n * 3..].chunks_exact_mut(6))
+    {
+        let yuv = <[Yuv<u8>; 2]>::from(Uyvy::<u8>::from([src[0], src[1], src[2], src[3]]));
+        dst[..3].copy_from_slice(&Rgb::<u8>::from(yuv[0]).0);
+        dst[3..].copy_from_slice(&Rgb::<u8>::from(yuv[1]).0);
+    }
+}
+
+/// Converts YUV 4:2:0 planes to RGB pixels.
+///
+/// Each chroma sample is shared by a block of 2x2 pixels, like in [`Yuv420p::pack_planes`].
+///
+/// # Panics
+///
+/// Panics if `width` or `height` is odd or if the planes or `dst` do not match the dimensions.
+///
+/// [`Yuv420p::pack_planes`]: crate::yuv420::Yuv420p::pack_planes
+pub fn yuv420p_to_rgb(y: &[u8], u: &[u8], v: &[u8], width: u32, height: u32, dst: &mut [u8]) {
+    yuv420p_to_rgb_with(Isa::detect(), y, u, v, width, height, dst)
+}
+
+fn yuv420p_to_rgb_with(
+    isa: Isa,
+    y: &[u8],
+    u: &[u8],
+    v: &[u8],
+    width: u32,
+    height: u32,
+    dst: &mut [u8],
+) {
+    let (width, height) = (width as usize, height as usize);
+    assert_eq!(width % 2, 0);
+    assert_eq!(height % 2, 0);
+    assert_eq!(y.len(), width * height);
+    assert_eq!(u.len(), width * height / 4);
+    assert_eq!(v.len(), width * height / 4);
+    assert_eq!(dst.len(), width * height * 3);
+
+    for (i, (y, dst)) in y
+        .chunks_exact(width)
+        .zip(dst.chunks_exact_mut(width * 3))
+        .enumerate()
+    {
+        let chroma = i / 2 * width / 2..(i / 2 + 1) * width / 2;
+        let (u, v) = (&u[chroma.clone()], &v[chroma]);
+
+        let n = match isa {
+            #[cfg(target_arch = "x86_64")]
+            Isa::Avx2 => unsafe { x86::yuv420p_to_rgb_avx2(y, u, v, dst) },
+            #[cfg(target_arch = "x86_64")]
+            Isa::Sse2 | Isa::Ssse3 => unsafe { x86::yuv420p_to_rgb_sse2(y, u, v, dst) },
+            #[cfg(target_arch = "aarch64")]
+            Isa::Neon => unsafe { aarch64::yuv420p_to_rgb_neon(y, u, v, dst) },
+            _ => 0,
+        };
+
+        for (j, dst) in dst.chunks_exact_mut(3).enumerate().skip(n) {
+            let rgb = Rgb::<u8>::from(Yuv::<u8>([y[j], u[j / 2], v[j / 2]]));
+            dst.copy_from_slice(&rgb.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::yuv420::Yuv420p;
+
+    /// Returns pseudo-random bytes from a linear congruential generator.
+    fn random<const N: usize>(seed: u32) -> [u8; N] {
+        let mut state = seed;
+        let mut buf = [0; N];
+        for x in buf.iter_mut() {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            *x = (state >> 16) as u8;
+        }
+        buf
+    }
+
+    fn isas() -> impl Iterator<Item = Isa> {
+        Isa::ALL.iter().copied().filter(|isa| isa.is_supported())
+    }
+
+    #[test]
+    fn yuv_to_rgb() {
+        // odd length to exercise the scalar tail
+        let src: [u8; 3 * 301] = random(1);
+
+        let mut expected = [0u8; 3 * 301];
+        yuv_to_rgb_with(Isa::Scalar, &src, &mut expected);
+
+        for isa in isas() {
+            let mut rgb = [0u8; 3 * 301];
+            yuv_to_rgb_with(isa, &src, &mut rgb);
+            assert_eq!(rgb[..], expected[..], "{:?}", isa);
+        }
+    }
+
+    #[test]
+    fn rgb_to_yuv() {
+        let mut src: [u8; 3 * 301] = random(2);
+        // extremes
+        src[..6].copy_from_slice(&[255, 255, 255, 0, 0, 0]);
+
+        let mut expected = [0u8; 3 * 301];
+        rgb_to_yuv_with(Isa::Scalar, &src, &mut expected);
+
+        for isa in isas() {
+            let mut yuv = [0u8; 3 * 301];
+            rgb_to_yuv_with(isa, &src, &mut yuv);
+            assert_eq!(yuv[..], expected[..], "{:?}", isa);
+        }
+    }
+
+    #[test]
+    fn yuv422_to_rgb() {
+        let src: [u8; 4 * 151] = random(3);
+
+        let mut expected = [0u8; 6 * 151];
+        yuyv_to_rgb_with(Isa::Scalar, &src, &mut expected);
+        for isa in isas() {
+            let mut rgb = [0u8; 6 * 151];
+            yuyv_to_rgb_with(isa, &src, &mut rgb);
+            assert_eq!(rgb[..], expected[..], "{:?}", isa);
+        }
+
+        uyvy_to_rgb_with(Isa::Scalar, &src, &mut expected);
+        for isa in isas() {
+            let mut rgb = [0u8; 6 * 151];
+            uyvy_to_rgb_with(isa, &src, &mut rgb);
+            assert_eq!(rgb[..], expected[..], "{:?}", isa);
+        }
+    }
+
+    #[test]
+    fn yuv420p_to_rgb() {
+        const W: usize = 70;
+        const H: usize = 6;
+        let y: [u8; W * H] = random(4);
+        let u: [u8; W * H / 4] = random(5);
+        let v: [u8; W * H / 4] = random(6);
+
+        let mut expected = [0u8; W * H * 3];
+        for (pix, dst) in Yuv420p::pack_planes(&y, &u, &v, W as u32, H as u32)
+            .into_iter()
+            .zip(expected.chunks_exact_mut(3))
+        {
+            dst.copy_from_slice(&Rgb::<u8>::from(Yuv::<u8>(pix)).0);
+        }
+
+        for isa in isas() {
+            let mut rgb = [0u8; W * H * 3];
+            yuv420p_to_rgb_with(isa, &y, &u, &v, W as u32, H as u32, &mut rgb);
+            assert_eq!(rgb[..], expected[..], "{:?}", isa);
+        }
+    }
+}
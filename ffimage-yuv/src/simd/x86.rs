@@ -0,0 +1,369 @@
+//! x86_64 kernels.
+
+use core::arch::x86_64::*;
+
+use ffimage::simd::x86::{even, load3, odd, store3};
+
+/// Returns the 16 bit pair `(a, b)` as 32 bit number, suitable for `madd`.
+#[inline(always)]
+fn pair(a: i16, b: i16) -> i32 {
+    (((b as u16 as u32) << 16) | a as u16 as u32) as i32
+}
+
+/// Vector width specific arithmetic, processing 32 pixels at a time.
+trait Math {
+    /// Converts YUV to RGB planes.
+    unsafe fn yuv_to_rgb(yuv: [[__m128i; 2]; 3]) -> [[__m128i; 2]; 3];
+
+    /// Converts RGB to YUV planes.
+    unsafe fn rgb_to_yuv(rgb: [[__m128i; 2]; 3]) -> [[__m128i; 2]; 3];
+}
+
+struct Sse2;
+
+impl Sse2 {
+    /// Returns `(a0 * k0 + b0 * k1, ...)` as 32 bit sums, split into the lower and upper half.
+    #[inline(always)]
+    unsafe fn dot(a: __m128i, b: __m128i, k: i32) -> [__m128i; 2] {
+        let k = _mm_set1_epi32(k);
+        [
+            _mm_madd_epi16(_mm_unpacklo_epi16(a, b), k),
+            _mm_madd_epi16(_mm_unpackhi_epi16(a, b), k),
+        ]
+    }
+
+    /// Returns the sums shifted right by 8 bits as 16 bit numbers.
+    #[inline(always)]
+    unsafe fn narrow(a: [__m128i; 2], b: [__m128i; 2]) -> __m128i {
+        _mm_packs_epi32(
+            _mm_srai_epi32(_mm_add_epi32(a[0], b[0]), 8),
+            _mm_srai_epi32(_mm_add_epi32(a[1], b[1]), 8),
+        )
+    }
+
+    #[inline(always)]
+    unsafe fn yuv_to_rgb8(y: __m128i, u: __m128i, v: __m128i) -> [__m128i; 3] {
+        let c = _mm_sub_epi16(y, _mm_set1_epi16(16));
+        let d = _mm_sub_epi16(u, _mm_set1_epi16(128));
+        let e = _mm_sub_epi16(v, _mm_set1_epi16(128));
+        let one = _mm_set1_epi16(1);
+        let round = [_mm_set1_epi32(128); 2];
+
+        [
+            Self::narrow(Self::dot(c, e, pair(298, 409)), round),
+            Self::narrow(
+                Self::dot(c, d, pair(298, -100)),
+                Self::dot(e, one, pair(-208, 128)),
+            ),
+            Self::narrow(Self::dot(c, d, pair(298, 516)), round),
+        ]
+    }
+
+    #[inline(always)]
+    unsafe fn rgb_to_yuv8(r: __m128i, g: __m128i, b: __m128i) -> [__m128i; 3] {
+        let one = _mm_set1_epi16(1);
+        let y = Self::narrow(
+            Self::dot(r, g, pair(66, 129)),
+            Self::dot(b, one, pair(25, 128)),
+        );
+        let u = Self::narrow(
+            Self::dot(r, g, pair(-38, -74)),
+            Self::dot(b, one, pair(112, 128)),
+        );
+        let v = Self::narrow(
+            Self::dot(r, g, pair(112, -94)),
+            Self::dot(b, one, pair(-18, 128)),
+        );
+
+        [
+            _mm_add_epi16(y, _mm_set1_epi16(16)),
+            _mm_add_epi16(u, _mm_set1_epi16(128)),
+            _mm_add_epi16(v, _mm_set1_epi16(128)),
+        ]
+    }
+}
+
+impl Math for Sse2 {
+    #[inline(always)]
+    unsafe fn yuv_to_rgb(yuv: [[__m128i; 2]; 3]) -> [[__m128i; 2]; 3] {
+        let zero = _mm_setzero_si128();
+        let mut rgb = [[zero; 2]; 3];
+        for i in 0..2 {
+            let lo = Self::yuv_to_rgb8(
+                _mm_unpacklo_epi8(yuv[0][i], zero),
+                _mm_unpacklo_epi8(yuv[1][i], zero),
+                _mm_unpacklo_epi8(yuv[2][i], zero),
+            );
+            let hi = Self::yuv_to_rgb8(
+                _mm_unpackhi_epi8(yuv[0][i], zero),
+                _mm_unpackhi_epi8(yuv[1][i], zero),
+                _mm_unpackhi_epi8(yuv[2][i], zero),
+            );
+            for c in 0..3 {
+                // saturates to 0..255, which is the clamping of the scalar code
+                rgb[c][i] = _mm_packus_epi16(lo[c], hi[c]);
+            }
+        }
+        rgb
+    }
+
+    #[inline(always)]
+    unsafe fn rgb_to_yuv(rgb: [[__m128i; 2]; 3]) -> [[__m128i; 2]; 3] {
+        let zero = _mm_setzero_si128();
+        let mut yuv = [[zero; 2]; 3];
+        for i in 0..2 {
+            let lo = Self::rgb_to_yuv8(
+                _mm_unpacklo_epi8(rgb[0][i], zero),
+                _mm_unpacklo_epi8(rgb[1][i], zero),
+                _mm_unpacklo_epi8(rgb[2][i], zero),
+            );
+            let hi = Self::rgb_to_yuv8(
+                _mm_unpackhi_epi8(rgb[0][i], zero),
+                _mm_unpackhi_epi8(rgb[1][i], zero),
+                _mm_unpackhi_epi8(rgb[2][i], zero),
+            );
+            for c in 0..3 {
+                yuv[c][i] = _mm_packus_epi16(lo[c], hi[c]);
+            }
+        }
+        yuv
+    }
+}
+
+struct Avx2;
+
+impl Avx2 {
+    #[inline(always)]
+    unsafe fn dot(a: __m256i, b: __m256i, k: i32) -> [__m256i; 2] {
+        let k = _mm256_set1_epi32(k);
+        [
+            _mm256_madd_epi16(_mm256_unpacklo_epi16(a, b), k),
+            _mm256_madd_epi16(_mm256_unpackhi_epi16(a, b), k),
+        ]
+    }
+
+    #[inline(always)]
+    unsafe fn narrow(a: [__m256i; 2], b: [__m256i; 2]) -> __m256i {
+        _mm256_packs_epi32(
+            _mm256_srai_epi32(_mm256_add_epi32(a[0], b[0]), 8),
+            _mm256_srai_epi32(_mm256_add_epi32(a[1], b[1]), 8),
+        )
+    }
+
+    #[inline(always)]
+    unsafe fn yuv_to_rgb16(y: __m256i, u: __m256i, v: __m256i) -> [__m256i; 3] {
+        let c = _mm256_sub_epi16(y, _mm256_set1_epi16(16));
+        let d = _mm256_sub_epi16(u, _mm256_set1_epi16(128));
+        let e = _mm256_sub_epi16(v, _mm256_set1_epi16(128));
+        let one = _mm256_set1_epi16(1);
+        let round = [_mm256_set1_epi32(128); 2];
+
+        [
+            Self::narrow(Self::dot(c, e, pair(298, 409)), round),
+            Self::narrow(
+                Self::dot(c, d, pair(298, -100)),
+                Self::dot(e, one, pair(-208, 128)),
+            ),
+            Self::narrow(Self::dot(c, d, pair(298, 516)), round),
+        ]
+    }
+
+    #[inline(always)]
+    unsafe fn rgb_to_yuv16(r: __m256i, g: __m256i, b: __m256i) -> [__m256i; 3] {
+        let one = _mm256_set1_epi16(1);
+        let y = Self::narrow(
+            Self::dot(r, g, pair(66, 129)),
+            Self::dot(b, one, pair(25, 128)),
+        );
+        let u = Self::narrow(
+            Self::dot(r, g, pair(-38, -74)),
+            Self::dot(b, one, pair(112, 128)),
+        );
+        let v = Self::narrow(
+            Self::dot(r, g, pair(112, -94)),
+            Self::dot(b, one, pair(-18, 128)),
+        );
+
+        [
+            _mm256_add_epi16(y, _mm256_set1_epi16(16)),
+            _mm256_add_epi16(u, _mm256_set1_epi16(128)),
+            _mm256_add_epi16(v, _mm256_set1_epi16(128)),
+        ]
+    }
+
+    /// Joins two 128 bit vectors into a 256 bit vector.
+    #[inline(always)]
+    unsafe fn join(v: [__m128i; 2]) -> __m256i {
+        _mm256_set_m128i(v[1], v[0])
+    }
+
+    /// Splits a 256 bit vector into two 128 bit vectors.
+    #[inline(always)]
+    unsafe fn split(v: __m256i) -> [__m128i; 2] {
+        [_mm256_castsi256_si128(v), _mm256_extracti128_si256(v, 1)]
+    }
+}
+
+impl Math for Avx2 {
+    // Unpacking and packing happens within the 128 bit lanes, so the pixel order is kept.
+
+    #[inline(always)]
+    unsafe fn yuv_to_rgb(yuv: [[__m128i; 2]; 3]) -> [[__m128i; 2]; 3] {
+        let zero = _mm256_setzero_si256();
+        let (y, u, v) = (Self::join(yuv[0]), Self::join(yuv[1]), Self::join(yuv[2]));
+        let lo = Self::yuv_to_rgb16(
+            _mm256_unpacklo_epi8(y, zero),
+            _mm256_unpacklo_epi8(u, zero),
+            _mm256_unpacklo_epi8(v, zero),
+        );
+        let hi = Self::yuv_to_rgb16(
+            _mm256_unpackhi_epi8(y, zero),
+            _mm256_unpackhi_epi8(u, zero),
+            _mm256_unpackhi_epi8(v, zero),
+        );
+
+        [
+            Self::split(_mm256_packus_epi16(lo[0], hi[0])),
+            Self::split(_mm256_packus_epi16(lo[1], hi[1])),
+            Self::split(_mm256_packus_epi16(lo[2], hi[2])),
+        ]
+    }
+
+    #[inline(always)]
+    unsafe fn rgb_to_yuv(rgb: [[__m128i; 2]; 3]) -> [[__m128i; 2]; 3] {
+        let zero = _mm256_setzero_si256();
+        let (r, g, b) = (Self::join(rgb[0]), Self::join(rgb[1]), Self::join(rgb[2]));
+        let lo = Self::rgb_to_yuv16(
+            _mm256_unpacklo_epi8(r, zero),
+            _mm256_unpacklo_epi8(g, zero),
+            _mm256_unpacklo_epi8(b, zero),
+        );
+        let hi = Self::rgb_to_yuv16(
+            _mm256_unpackhi_epi8(r, zero),
+            _mm256_unpackhi_epi8(g, zero),
+            _mm256_unpackhi_epi8(b, zero),
+        );
+
+        [
+            Self::split(_mm256_packus_epi16(lo[0], hi[0])),
+            Self::split(_mm256_packus_epi16(lo[1], hi[1])),
+            Self::split(_mm256_packus_epi16(lo[2], hi[2])),
+        ]
+    }
+}
+
+#[inline(always)]
+unsafe fn load(ptr: *const u8) -> __m128i {
+    _mm_loadu_si128(ptr as *const __m128i)
+}
+
+#[inline(always)]
+unsafe fn yuv_to_rgb<M: Math>(src: &[u8], dst: &mut [u8]) -> usize {
+    let mut n = 0;
+    while (n + 32) * 3 <= src.len() {
+        let yuv = load3(src.as_ptr().add(n * 3));
+        store3(dst.as_mut_ptr().add(n * 3), M::yuv_to_rgb(yuv));
+        n += 32;
+    }
+
+    n
+}
+
+#[inline(always)]
+unsafe fn rgb_to_yuv<M: Math>(src: &[u8], dst: &mut [u8]) -> usize {
+    let mut n = 0;
+    while (n + 32) * 3 <= src.len() {
+        let rgb = load3(src.as_ptr().add(n * 3));
+        store3(dst.as_mut_ptr().add(n * 3), M::rgb_to_yuv(rgb));
+        n += 32;
+    }
+
+    n
+}
+
+/// Converts packed 4:2:2 pixels, `Y_ODD` tells whether luma is stored at odd (UYVY) or even
+/// (YUYV) positions.
+#[inline(always)]
+unsafe fn yuv422_to_rgb<M: Math, const Y_ODD: bool>(src: &[u8], dst: &mut [u8]) -> usize {
+    let mut n = 0;
+    while (n + 32) * 2 <= src.len() {
+        let ptr = src.as_ptr().add(n * 2);
+        let v = [
+            load(ptr),
+            load(ptr.add(16)),
+            load(ptr.add(32)),
+            load(ptr.add(48)),
+        ];
+        let (y, uv) = if Y_ODD {
+            (
+                [odd(v[0], v[1]), odd(v[2], v[3])],
+                [even(v[0], v[1]), even(v[2], v[3])],
+            )
+        } else {
+            (
+                [even(v[0], v[1]), even(v[2], v[3])],
+                [odd(v[0], v[1]), odd(v[2], v[3])],
+            )
+        };
+
+        // each chroma sample is shared by two pixels
+        let u = even(uv[0], uv[1]);
+        let v = odd(uv[0], uv[1]);
+        let yuv = [
+            y,
+            [_mm_unpacklo_epi8(u, u), _mm_unpackhi_epi8(u, u)],
+            [_mm_unpacklo_epi8(v, v), _mm_unpackhi_epi8(v, v)],
+        ];
+
+        store3(dst.as_mut_ptr().add(n * 3), M::yuv_to_rgb(yuv));
+        n += 32;
+    }
+
+    n
+}
+
+/// Converts a row of 4:2:0 planar pixels, `u` and `v` hold the chroma samples of the row.
+#[inline(always)]
+unsafe fn yuv420p_to_rgb<M: Math>(y: &[u8], u: &[u8], v: &[u8], dst: &mut [u8]) -> usize {
+    let mut n = 0;
+    while n + 32 <= y.len() {
+        let cu = load(u.as_ptr().add(n / 2));
+        let cv = load(v.as_ptr().add(n / 2));
+        let yuv = [
+            [load(y.as_ptr().add(n)), load(y.as_ptr().add(n + 16))],
+            [_mm_unpacklo_epi8(cu, cu), _mm_unpackhi_epi8(cu, cu)],
+            [_mm_unpacklo_epi8(cv, cv), _mm_unpackhi_epi8(cv, cv)],
+        ];
+
+        store3(dst.as_mut_ptr().add(n * 3), M::yuv_to_rgb(yuv));
+        n += 32;
+    }
+
+    n
+}
+
+macro_rules! kernel {
+    ($sse2:ident, $avx2:ident, $generic:ident$(::<$($param:tt),*>)?, ($($arg:ident: $ty:ty),*)) => {
+        /// Returns the number of converted pixels.
+        pub(crate) unsafe fn $sse2($($arg: $ty),*) -> usize {
+            $generic::<Sse2 $($(, $param)*)?>($($arg),*)
+        }
+
+        /// Returns the number of converted pixels.
+        #[target_feature(enable = "avx2")]
+        pub(crate) unsafe fn $avx2($($arg: $ty),*) -> usize {
+            $generic::<Avx2 $($(, $param)*)?>($($arg),*)
+        }
+    };
+}
+
+kernel!(yuv_to_rgb_sse2, yuv_to_rgb_avx2, yuv_to_rgb, (src: &[u8], dst: &mut [u8]));
+kernel!(rgb_to_yuv_sse2, rgb_to_yuv_avx2, rgb_to_yuv, (src: &[u8], dst: &mut [u8]));
+kernel!(yuyv_to_rgb_sse2, yuyv_to_rgb_avx2, yuv422_to_rgb::<false>, (src: &[u8], dst: &mut [u8]));
+kernel!(uyvy_to_rgb_sse2, uyvy_to_rgb_avx2, yuv422_to_rgb::<true>, (src: &[u8], dst: &mut [u8]));
+kernel!(
+    yuv420p_to_rgb_sse2,
+    yuv420p_to_rgb_avx2,
+    yuv420p_to_rgb,
+    (y: &[u8], u: &[u8], v: &[u8], dst: &mut [u8])
+);
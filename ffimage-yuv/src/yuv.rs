@@ -3,15 +3,24 @@ use core::{
     ops::{Deref, DerefMut},
 };
 
-use num_traits::{AsPrimitive, FromPrimitive};
+use num_traits::{AsPrimitive, Bounded, FromPrimitive};
 
 use ffimage::color::rgb::*;
-use ffimage::Pixel;
+use ffimage::sample::{FromDepth, Sample};
+use ffimage::{Error, Pixel};
 
 /// YUV pixel
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
 pub struct Yuv<T, const Y: usize = 0, const U: usize = 1, const V: usize = 2>(pub [T; 3]);
 
+// SAFETY: transparent wrapper of [T; 3]
+unsafe impl<T: Copy, const Y: usize, const U: usize, const V: usize> ffimage::cast::Transparent
+    for Yuv<T, Y, U, V>
+{
+    type Sample = T;
+}
+
 impl<T, const Y: usize, const U: usize, const V: usize> From<[T; 3]> for Yuv<T, Y, U, V> {
     fn from(value: [T; 3]) -> Self {
         Yuv(value)
@@ -36,6 +45,195 @@ impl<T, const Y: usize, const U: usize, const V: usize> Pixel for Yuv<T, Y, U, V
     const CHANNELS: u8 = 3;
 }
 
+impl<S, T, const Y: usize, const U: usize, const V: usize> FromDepth<Yuv<S, Y, U, V>>
+    for Yuv<T, Y, U, V>
+where
+    S: Sample,
+    T: Sample,
+{
+    fn from_depth(yuv: Yuv<S, Y, U, V>) -> Self {
+        Yuv([yuv[0].to_sample(), yuv[1].to_sample(), yuv[2].to_sample()])
+    }
+}
+
+/// Converts RGB to YUV values (BT.601, limited range).
+fn rgb_to_yuv(r: i32, g: i32, b: i32) -> [i32; 3] {
+    [
+        ((66 * r + 129 * g + 25 * b + 128) >> 8) + 16,
+        ((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128,
+        ((112 * r - 94 * g - 18 * b + 128) >> 8) + 128,
+    ]
+}
+
+/// Converts YUV (BT.601, limited range) to RGB values.
+fn yuv_to_rgb(y: i32, u: i32, v: i32) -> [i32; 3] {
+    let c = y - 16;
+    let d = u - 128;
+    let e = v - 128;
+
+    [
+        ((298 * c + 409 * e + 128) >> 8).clamp(0, 255),
+        ((298 * c - 100 * d - 208 * e + 128) >> 8).clamp(0, 255),
+        ((298 * c + 516 * d + 128) >> 8).clamp(0, 255),
+    ]
+}
+
+/// Converts normalized RGB to normalized YUV values (BT.601, limited range).
+fn rgb_to_yuv_f32(r: f32, g: f32, b: f32) -> [f32; 3] {
+    [
+        (16.0 + 65.481 * r + 128.553 * g + 24.966 * b) / 255.0,
+        (128.0 - 37.797 * r - 74.203 * g + 112.0 * b) / 255.0,
+        (128.0 + 112.0 * r - 93.786 * g - 18.214 * b) / 255.0,
+    ]
+}
+
+/// Converts normalized YUV (BT.601, limited range) to normalized RGB values.
+fn yuv_to_rgb_f32(y: f32, u: f32, v: f32) -> [f32; 3] {
+    let c = y * 255.0 - 16.0;
+    let d = u * 255.0 - 128.0;
+    let e = v * 255.0 - 128.0;
+
+    [
+        ((1.164383 * c + 1.596027 * e) / 255.0).clamp(0.0, 1.0),
+        ((1.164383 * c - 0.391762 * d - 0.812968 * e) / 255.0).clamp(0.0, 1.0),
+        ((1.164383 * c + 2.017232 * d) / 255.0).clamp(0.0, 1.0),
+    ]
+}
+
+/// Converts a value to `T`, saturating at the bounds of `T`.
+fn saturate<T: FromPrimitive + Bounded>(value: i32) -> T {
+    T::from_i32(value).unwrap_or_else(|| {
+        if value < 0 {
+            T::min_value()
+        } else {
+            T::max_value()
+        }
+    })
+}
+
+/// Converts a value to `T`, fails if it cannot be represented by `T`.
+fn checked<T: FromPrimitive>(value: i32) -> Result<T, Error> {
+    T::from_i32(value).ok_or(Error::OutOfRange)
+}
+
+/// Sample types which YUV values can be computed for.
+///
+/// Integer samples hold 8 bit code values (BT.601, limited range), e.g. Y in 16..=235 for black
+/// to white. Float samples hold the same values normalized like [`Sample`] does, i.e. divided by
+/// 255, so U and V are centred on 128 / 255 (about 0.5).
+pub trait YuvSample: Copy {
+    /// Returns the YUV values of RGB values, saturated to the range of the sample type.
+    fn from_rgb(rgb: [Self; 3]) -> [Self; 3];
+
+    /// Returns the RGB values of YUV values, saturated to the range of the sample type.
+    fn to_rgb(yuv: [Self; 3]) -> [Self; 3];
+
+    /// Returns the YUV values of RGB values, fails if a value cannot be represented.
+    fn try_from_rgb(rgb: [Self; 3]) -> Result<[Self; 3], Error>;
+
+    /// Returns the RGB values of YUV values, fails if a value cannot be represented.
+    fn try_to_rgb(yuv: [Self; 3]) -> Result<[Self; 3], Error>;
+}
+
+macro_rules! impl_yuv_sample_int {
+    ($($t:ty),+) => {
+        $(
+            impl YuvSample for $t {
+                fn from_rgb(rgb: [Self; 3]) -> [Self; 3] {
+                    rgb_to_yuv(rgb[0].as_(), rgb[1].as_(), rgb[2].as_()).map(saturate)
+                }
+
+                fn to_rgb(yuv: [Self; 3]) -> [Self; 3] {
+                    yuv_to_rgb(yuv[0].as_(), yuv[1].as_(), yuv[2].as_()).map(saturate)
+                }
+
+                fn try_from_rgb(rgb: [Self; 3]) -> Result<[Self; 3], Error> {
+                    let [y, u, v] = rgb_to_yuv(rgb[0].as_(), rgb[1].as_(), rgb[2].as_());
+                    Ok([checked(y)?, checked(u)?, checked(v)?])
+                }
+
+                fn try_to_rgb(yuv: [Self; 3]) -> Result<[Self; 3], Error> {
+                    let [r, g, b] = yuv_to_rgb(yuv[0].as_(), yuv[1].as_(), yuv[2].as_());
+                    Ok([checked(r)?, checked(g)?, checked(b)?])
+                }
+            }
+        )+
+    };
+}
+
+impl_yuv_sample_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_yuv_sample_float {
+    ($t:ty, $to_f32:expr, $from_f32:expr) => {
+        impl YuvSample for $t {
+            fn from_rgb(rgb: [Self; 3]) -> [Self; 3] {
+                let [r, g, b] = rgb.map($to_f32);
+                rgb_to_yuv_f32(r, g, b).map($from_f32)
+            }
+
+            fn to_rgb(yuv: [Self; 3]) -> [Self; 3] {
+                let [y, u, v] = yuv.map($to_f32);
+                yuv_to_rgb_f32(y, u, v).map($from_f32)
+            }
+
+            fn try_from_rgb(rgb: [Self; 3]) -> Result<[Self; 3], Error> {
+                // all values are representable
+                Ok(Self::from_rgb(rgb))
+            }
+
+            fn try_to_rgb(yuv: [Self; 3]) -> Result<[Self; 3], Error> {
+                Ok(Self::to_rgb(yuv))
+            }
+        }
+    };
+}
+
+impl_yuv_sample_float!(f32, |v| v, |v| v);
+impl_yuv_sample_float!(f64, |v| v as f32, |v| v as f64);
+#[cfg(feature = "f16")]
+impl_yuv_sample_float!(
+    ffimage::sample::f16,
+    ffimage::sample::f16::to_f32,
+    ffimage::sample::f16::from_f32
+);
+
+impl<T, const Y: usize, const U: usize, const V: usize> Yuv<T, Y, U, V>
+where
+    T: YuvSample + Default,
+{
+    /// Converts an RGB pixel, fails if a YUV value cannot be represented by `T`.
+    ///
+    /// The `From` implementation saturates such values instead.
+    pub fn try_from_rgb<const R: usize, const G: usize, const B: usize>(
+        rgb: Rgb<T, R, G, B>,
+    ) -> Result<Self, Error> {
+        let [y, u, v] = T::try_from_rgb([rgb[R], rgb[G], rgb[B]])?;
+
+        let mut yuv = Self::default();
+        yuv[Y] = y;
+        yuv[U] = u;
+        yuv[V] = v;
+        Ok(yuv)
+    }
+
+    /// Converts the pixel to RGB, fails if an RGB value cannot be represented by `T`.
+    ///
+    /// The `From` implementation saturates such values instead.
+    pub fn try_to_rgb<const R: usize, const G: usize, const B: usize>(
+        self,
+    ) -> Result<Rgb<T, R, G, B>, Error> {
+        let [r, g, b] = T::try_to_rgb([self[Y], self[U], self[V]])?;
+
+        let mut rgb = Rgb::<T, R, G, B>::default();
+        rgb[R] = r;
+        rgb[G] = g;
+        rgb[B] = b;
+        Ok(rgb)
+    }
+}
+
+/// Values which cannot be represented by `T` are saturated, see [`Yuv::try_from_rgb`] for a
+/// checked conversion.
 impl<
         T,
         const Y: usize,
@@ -46,25 +244,21 @@ impl<
         const B: usize,
     > From<Rgb<T, R, G, B>> for Yuv<T, Y, U, V>
 where
-    T: Copy + Default + AsPrimitive<i32> + FromPrimitive,
+    T: YuvSample + Default,
 {
     fn from(rgb: Rgb<T, R, G, B>) -> Self {
-        let r = rgb[R].as_();
-        let g = rgb[G].as_();
-        let b = rgb[B].as_();
-
-        let y = ((66 * r + 129 * g + 25 * b + 128) >> 8) + 16;
-        let u = ((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128;
-        let v = ((112 * r - 94 * g - 18 * b + 128) >> 8) + 128;
+        let [y, u, v] = T::from_rgb([rgb[R], rgb[G], rgb[B]]);
 
         let mut yuv = Yuv::<T, Y, U, V>::default();
-        yuv[Y] = T::from_i32(y).unwrap();
-        yuv[U] = T::from_i32(u).unwrap();
-        yuv[V] = T::from_i32(v).unwrap();
+        yuv[Y] = y;
+        yuv[U] = u;
+        yuv[V] = v;
         yuv
     }
 }
 
+/// Values which cannot be represented by `T` are saturated, see [`Yuv::try_to_rgb`] for a
+/// checked conversion.
 impl<
         T,
         const R: usize,
@@ -75,24 +269,15 @@ impl<
         const V: usize,
     > From<Yuv<T, Y, U, V>> for Rgb<T, R, G, B>
 where
-    T: Copy + Default + AsPrimitive<i32> + FromPrimitive,
+    T: YuvSample + Default,
 {
     fn from(yuv: Yuv<T, Y, U, V>) -> Self {
-        let y = yuv[Y].as_();
-        let u = yuv[U].as_();
-        let v = yuv[V].as_();
-        let c = y - 16;
-        let d = u - 128;
-        let e = v - 128;
-
-        let r = ((298 * c + 409 * e + 128) >> 8).clamp(0, 255);
-        let g = ((298 * c - 100 * d - 208 * e + 128) >> 8).clamp(0, 255);
-        let b = ((298 * c + 516 * d + 128) >> 8).clamp(0, 255);
+        let [r, g, b] = T::to_rgb([yuv[Y], yuv[U], yuv[V]]);
 
         let mut rgb = Rgb::<T, R, G, B>::default();
-        rgb[R] = T::from_i32(r).unwrap();
-        rgb[G] = T::from_i32(g).unwrap();
-        rgb[B] = T::from_i32(b).unwrap();
+        rgb[R] = r;
+        rgb[G] = g;
+        rgb[B] = b;
         rgb
     }
 }
@@ -105,4 +290,96 @@ mod tests {
     fn channels() {
         assert_eq!(Yuv::<u8>::CHANNELS, 3);
     }
+
+    #[test]
+    fn from_depth() {
+        let yuv = Yuv::<u8>([16, 128, 255]);
+
+        assert_eq!(
+            Yuv::<u16>::from_depth(yuv),
+            Yuv([16 * 257, 128 * 257, u16::MAX])
+        );
+        assert_eq!(Yuv::<u8>::from_depth(Yuv::<u16>::from_depth(yuv)), yuv);
+    }
+
+    #[test]
+    fn out_of_range() {
+        let rgb = Rgb::<i8>([100, 100, 100]);
+
+        assert_eq!(Yuv::<i8>::from(rgb), Yuv([102, 127, 127]));
+        assert_eq!(Yuv::<i8>::try_from_rgb(rgb), Err(Error::OutOfRange));
+        assert_eq!(
+            Yuv::<u8>::try_from_rgb(Rgb::<u8>([100, 100, 100])),
+            Ok(Yuv([102, 128, 128]))
+        );
+
+        let yuv = Yuv::<i8>([127, 0, 0]);
+        assert_eq!(Rgb::<i8>::from(yuv), Rgb([0, 127, 0]));
+        assert_eq!(yuv.try_to_rgb::<0, 1, 2>(), Err(Error::OutOfRange));
+    }
+
+    #[cfg(feature = "f16")]
+    #[test]
+    fn from_depth_f16() {
+        use ffimage::sample::f16;
+
+        let yuv = Yuv::<u8>([16, 128, 255]);
+
+        assert_eq!(Yuv::<f16>::from_depth(yuv)[2], f16::ONE);
+        assert_eq!(Yuv::<u8>::from_depth(Yuv::<f16>::from_depth(yuv)), yuv);
+    }
+
+    /// The float path is more precise than the 8 bit integer path, allow rounding differences.
+    fn close(a: &[u8; 3], b: &[u8; 3]) -> bool {
+        a.iter()
+            .zip(b.iter())
+            .all(|(a, b)| (*a as i32 - *b as i32).abs() <= 1)
+    }
+
+    #[test]
+    fn float() {
+        let yuv = Yuv::<f32>::from(Rgb::<f32>([0.0; 3]));
+        assert_eq!(Yuv::<u8>::from_depth(yuv), Yuv([16, 128, 128]));
+
+        let yuv = Yuv::<f32>::from(Rgb::<f32>([1.0; 3]));
+        assert_eq!(Yuv::<u8>::from_depth(yuv), Yuv([235, 128, 128]));
+
+        let yuv = Yuv::<f64>::from(Rgb::<f64>([1.0; 3]));
+        assert!((yuv[0] - 235.0 / 255.0).abs() < 1e-6);
+
+        // saturated red
+        let rgb = Rgb::<f32>::from(Yuv::<f32>::from_depth(Yuv::<u8>([81, 90, 240])));
+        assert!(close(&Rgb::<u8>::from_depth(rgb), &[255, 0, 0]));
+    }
+
+    #[cfg(feature = "f16")]
+    #[test]
+    fn colorconvert_f16() {
+        use ffimage::{iter::ColorConvertExt, sample::f16};
+
+        let yuv = [
+            Yuv::<u8>([16, 128, 128]),
+            Yuv([102, 128, 128]),
+            Yuv([81, 90, 240]),
+            Yuv([145, 54, 34]),
+            Yuv([235, 128, 128]),
+        ];
+
+        // normalized samples round-trip through the float path
+        let rgb = yuv
+            .iter()
+            .copied()
+            .map(Yuv::<f16>::from_depth)
+            .colorconvert::<Rgb<f16>>();
+        for (rgb, yuv) in rgb.zip(yuv.iter().copied()) {
+            let rgb = Rgb::<u8>::from_depth(rgb);
+            assert!(close(&rgb, &Rgb::<u8>::from(yuv)), "{:?} {:?}", yuv, rgb);
+
+            let out = Yuv::<u8>::from_depth(Yuv::<f16>::from(Rgb::<f16>::from_depth(rgb)));
+            assert!(close(&out, &Yuv::<u8>::from(rgb)), "{:?} {:?}", rgb, out);
+        }
+
+        let yuv = Yuv::<f16>::from(Rgb::<f16>([f16::ZERO; 3]));
+        assert_eq!(Yuv::<u8>::from_depth(yuv), Yuv([16, 128, 128]));
+    }
 }
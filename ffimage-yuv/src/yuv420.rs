@@ -19,7 +19,7 @@ impl Yuv420p {
     {
         Self::pack_bytes(buf, width, height)
             .into_iter()
-            .map(|chunk| Yuv::<T>::from(chunk))
+            .map(Yuv::<T>::from)
     }
 
     /// Returns packed Yuv444 color samples as byte chunks from a given slice.
@@ -63,16 +63,13 @@ impl Yuv420p {
         // YUV420 has 2x2 blocks of Luma (y) samples
         assert_eq!(y.len() % 4, 0);
 
-        (0..height as usize)
-            .into_iter()
-            .map(move |i| {
-                (0..width as usize).into_iter().map(move |j| {
-                    let y_idx = i * width as usize + j;
-                    let uv_idx = i / 2 * width as usize / 2 + j / 2;
-                    [y[y_idx], u[uv_idx], v[uv_idx]]
-                })
+        (0..height as usize).flat_map(move |i| {
+            (0..width as usize).map(move |j| {
+                let y_idx = i * width as usize + j;
+                let uv_idx = i / 2 * width as usize / 2 + j / 2;
+                [y[y_idx], u[uv_idx], v[uv_idx]]
             })
-            .flatten()
+        })
     }
 
     /// Unpacks packed Yuv444 color samples into Y, U, V planes.
@@ -1,6 +1,9 @@
 use core::ops::{Deref, DerefMut};
 
-use ffimage::Pixel;
+use ffimage::{
+    sample::{FromDepth, Sample},
+    Pixel,
+};
 
 use crate::yuv::*;
 
@@ -9,6 +12,7 @@ pub type Yuyv<T> = Yuv422<T, 0, 2, 1, 3>;
 pub type Uyvy<T> = Yuv422<T, 1, 3, 0, 2>;
 
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(transparent)]
 pub struct Yuv422<
     T,
     const Y0: usize = 0,
@@ -17,6 +21,13 @@ pub struct Yuv422<
     const V: usize = 3,
 >(pub [T; 4]);
 
+// SAFETY: transparent wrapper of [T; 4]
+unsafe impl<T: Copy, const Y0: usize, const Y1: usize, const U: usize, const V: usize>
+    ffimage::cast::Transparent for Yuv422<T, Y0, Y1, U, V>
+{
+    type Sample = T;
+}
+
 impl<T, const Y0: usize, const Y1: usize, const U: usize, const V: usize> From<[T; 4]>
     for Yuv422<T, Y0, Y1, U, V>
 {
@@ -78,6 +89,22 @@ where
     }
 }
 
+impl<S, T, const Y0: usize, const Y1: usize, const U: usize, const V: usize>
+    FromDepth<Yuv422<S, Y0, Y1, U, V>> for Yuv422<T, Y0, Y1, U, V>
+where
+    S: Sample,
+    T: Sample,
+{
+    fn from_depth(pix: Yuv422<S, Y0, Y1, U, V>) -> Self {
+        Yuv422([
+            pix[0].to_sample(),
+            pix[1].to_sample(),
+            pix[2].to_sample(),
+            pix[3].to_sample(),
+        ])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -86,4 +113,11 @@ mod tests {
     fn channels() {
         assert_eq!(Yuv422::<u8, 0, 2, 1, 3>::CHANNELS, 4);
     }
+
+    #[test]
+    fn from_depth() {
+        let yuyv = Yuyv::<u16>::from([0, 0x8080, 0xffff, 0x0101]);
+
+        assert_eq!(Yuyv::<u8>::from_depth(yuyv), Yuyv::from([0, 128, 255, 1]));
+    }
 }
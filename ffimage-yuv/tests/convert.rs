@@ -1,3 +1,11 @@
+#![allow(
+    clippy::implicit_saturating_sub,
+    clippy::map_flatten,
+    clippy::redundant_closure,
+    clippy::useless_conversion,
+    clippy::useless_vec
+)]
+
 use core::ops::RangeInclusive;
 
 use ffimage::color::Rgb;
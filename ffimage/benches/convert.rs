@@ -1,8 +1,11 @@
+#![allow(clippy::identity_op)]
+
 use criterion::{black_box, criterion_group, Criterion};
 
 use ffimage::{
-    color::{Bgr, Gray, Rgb},
+    color::{Bgr, Bt709, Gray, Rgb},
     iter::{BytesExt, ColorConvertExt, PixelsExt},
+    simd,
 };
 
 pub fn rgb_to_bgr(c: &mut Criterion) {
@@ -22,6 +25,11 @@ pub fn rgb_to_bgr(c: &mut Criterion) {
                     .write(black_box(&mut bgr))
             })
         });
+
+        c.bench_function(
+            &format!("Rgb[u8] -> Bgr[u8] (simd) ({}x{})", res.0, res.1),
+            |b| b.iter(|| simd::rgb_to_bgr(&rgb, black_box(&mut bgr))),
+        );
     }
 }
 
@@ -42,6 +50,11 @@ pub fn rgb_to_gray(c: &mut Criterion) {
                     .write(black_box(&mut gray))
             })
         });
+
+        c.bench_function(
+            &format!("Rgb[u8] -> Gray[u8] (simd) ({}x{})", res.0, res.1),
+            |b| b.iter(|| simd::rgb_to_gray::<Bt709>(&rgb, black_box(&mut gray))),
+        );
     }
 }
 
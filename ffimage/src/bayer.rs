@@ -0,0 +1,391 @@
+//! Raw Bayer color filter array (CFA) images.
+//!
+//! Sensors with a color filter array capture a single color channel per pixel. A Bayer frame is
+//! represented by an [`ImageView`] of [`Gray`] pixels, [`demosaic`] interpolates the missing
+//! channels to produce a full [`Rgb`] frame:
+//!
+//! ```
+//! use ffimage::{
+//!     bayer::{demosaic, CfaPattern, Demosaic},
+//!     color::{Gray, Rgb},
+//!     image::{ImageView, ImageViewMut},
+//! };
+//!
+//! let raw = [255u8, 0, 0, 0];
+//! let mut rgb = [0u8; 12];
+//!
+//! let src = ImageView::<Gray<u8>, u8>::new(&raw, 2, 2).unwrap();
+//! let mut dst = ImageViewMut::<Rgb<u8>, u8>::new(&mut rgb, 2, 2).unwrap();
+//! demosaic(src, &mut dst, CfaPattern::Rggb, Demosaic::Nearest);
+//! assert_eq!(rgb, [255, 0, 0, 255, 0, 0, 255, 0, 0, 255, 0, 0]);
+//! ```
+//!
+//! Samples of any depth are supported, e.g. `u8`, `u16` or [`crate::sample::U12`] for 12-bit
+//! sensors.
+
+use crate::{
+    color::{Gray, Rgb},
+    image::{ImageView, ImageViewMut},
+    sample::Sample,
+};
+
+const RED: usize = 0;
+const GREEN: usize = 1;
+const BLUE: usize = 2;
+
+/// Arrangement of the color filters, named after the colors of the top left 2x2 pixel block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CfaPattern {
+    /// Red, green in the first row and green, blue in the second row
+    Rggb,
+    /// Blue, green in the first row and green, red in the second row
+    Bggr,
+    /// Green, red in the first row and blue, green in the second row
+    Grbg,
+    /// Green, blue in the first row and red, green in the second row
+    Gbrg,
+}
+
+impl CfaPattern {
+    /// Returns the color channel (red, green or blue) which is captured at the given position.
+    fn color(self, x: usize, y: usize) -> usize {
+        let quad = match self {
+            CfaPattern::Rggb => [RED, GREEN, GREEN, BLUE],
+            CfaPattern::Bggr => [BLUE, GREEN, GREEN, RED],
+            CfaPattern::Grbg => [GREEN, RED, BLUE, GREEN],
+            CfaPattern::Gbrg => [GREEN, BLUE, RED, GREEN],
+        };
+
+        quad[(y % 2) * 2 + x % 2]
+    }
+}
+
+/// Demosaicing algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Demosaic {
+    /// Copies the missing channels from the neighboring pixels of the same 2x2 block.
+    ///
+    /// This is the fastest algorithm, but produces blocky images with strong color fringes.
+    Nearest,
+    /// Averages the missing channels from the surrounding 3x3 pixels.
+    Bilinear,
+    /// Gradient-corrected linear interpolation by Malvar, He and Cutler.
+    ///
+    /// Uses 5x5 kernels which take the luminance gradient into account, which greatly reduces
+    /// the color fringes along edges at a small cost compared to bilinear interpolation.
+    Malvar,
+}
+
+/// Bilinear interpolation weights for all pixels of the same color in the 3x3 neighborhood
+const BILINEAR: [[i32; 3]; 3] = [[1, 2, 1], [2, 4, 2], [1, 2, 1]];
+
+// Malvar-He-Cutler kernels, scaled by 16
+
+/// Green at red and blue pixels
+const MHC_G: [[i32; 5]; 5] = [
+    [0, 0, -2, 0, 0],
+    [0, 0, 4, 0, 0],
+    [-2, 4, 8, 4, -2],
+    [0, 0, 4, 0, 0],
+    [0, 0, -2, 0, 0],
+];
+
+/// Red (blue) at green pixels with red (blue) horizontal neighbors
+const MHC_ROW: [[i32; 5]; 5] = [
+    [0, 0, 1, 0, 0],
+    [0, -2, 0, -2, 0],
+    [-2, 8, 10, 8, -2],
+    [0, -2, 0, -2, 0],
+    [0, 0, 1, 0, 0],
+];
+
+/// Red (blue) at green pixels with red (blue) vertical neighbors
+const MHC_COL: [[i32; 5]; 5] = [
+    [0, 0, -2, 0, 0],
+    [0, -2, 8, -2, 0],
+    [1, 0, 10, 0, 1],
+    [0, -2, 8, -2, 0],
+    [0, 0, -2, 0, 0],
+];
+
+/// Red at blue pixels and blue at red pixels
+const MHC_DIAG: [[i32; 5]; 5] = [
+    [0, 0, -3, 0, 0],
+    [0, 4, 0, 4, 0],
+    [-3, 0, 12, 0, -3],
+    [0, 4, 0, 4, 0],
+    [0, 0, -3, 0, 0],
+];
+
+/// Returns the index of a coordinate which is mirrored at the image borders.
+///
+/// Mirroring keeps the parity of the coordinate, so the color filter pattern is preserved.
+fn mirror(i: isize, len: usize) -> usize {
+    let len = len as isize;
+    let i = if i < 0 {
+        -i
+    } else if i >= len {
+        2 * (len - 1) - i
+    } else {
+        i
+    };
+
+    i.clamp(0, len - 1) as usize
+}
+
+/// Raw frame with mirrored border access
+struct Raw<'a, T> {
+    frame: ImageView<'a, Gray<T>, T>,
+    width: usize,
+    height: usize,
+    pattern: CfaPattern,
+}
+
+impl<'a, T: Sample> Raw<'a, T> {
+    fn get(&self, x: isize, y: isize) -> f32 {
+        let x = mirror(x, self.width);
+        let y = mirror(y, self.height);
+        self.frame.row(y as u32).unwrap()[x].to_normalized()
+    }
+
+    fn color(&self, x: isize, y: isize) -> usize {
+        self.pattern
+            .color(mirror(x, self.width), mirror(y, self.height))
+    }
+
+    /// Returns the normalized weighted sum of all neighbors of the given color.
+    fn filter<const N: usize>(
+        &self,
+        x: isize,
+        y: isize,
+        color: usize,
+        kernel: &[[i32; N]; N],
+    ) -> f32 {
+        let r = (N / 2) as isize;
+        let mut sum = 0.0;
+        let mut weights = 0;
+
+        for (ky, row) in kernel.iter().enumerate() {
+            for (kx, weight) in row.iter().enumerate() {
+                let (x, y) = (x + kx as isize - r, y + ky as isize - r);
+                if *weight != 0 && self.color(x, y) == color {
+                    sum += *weight as f32 * self.get(x, y);
+                    weights += weight;
+                }
+            }
+        }
+
+        sum / weights as f32
+    }
+
+    /// Returns the neighborhood convolved with a kernel which is scaled by 16.
+    fn convolve(&self, x: isize, y: isize, kernel: &[[i32; 5]; 5]) -> f32 {
+        let mut sum = 0.0;
+
+        for (ky, row) in kernel.iter().enumerate() {
+            for (kx, weight) in row.iter().enumerate() {
+                if *weight != 0 {
+                    sum += *weight as f32 * self.get(x + kx as isize - 2, y + ky as isize - 2);
+                }
+            }
+        }
+
+        sum / 16.0
+    }
+
+    fn nearest(&self, x: isize, y: isize) -> [f32; 3] {
+        let color = self.color(x, y);
+        // origin of the 2x2 block
+        let (x0, y0) = (x & !1, y & !1);
+        let mut rgb = [0.0; 3];
+
+        for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let c = self.color(x0 + dx, y0 + dy);
+            // prefer the green pixel in the same row
+            if c != GREEN || (color != GREEN && y0 + dy == y) {
+                rgb[c] = self.get(x0 + dx, y0 + dy);
+            }
+        }
+        rgb[color] = self.get(x, y);
+        rgb
+    }
+
+    fn bilinear(&self, x: isize, y: isize) -> [f32; 3] {
+        let mut rgb = [
+            self.filter(x, y, RED, &BILINEAR),
+            self.filter(x, y, GREEN, &BILINEAR),
+            self.filter(x, y, BLUE, &BILINEAR),
+        ];
+        rgb[self.color(x, y)] = self.get(x, y);
+        rgb
+    }
+
+    fn malvar(&self, x: isize, y: isize) -> [f32; 3] {
+        let color = self.color(x, y);
+        let mut rgb = [0.0; 3];
+        rgb[color] = self.get(x, y);
+
+        if color == GREEN {
+            let (row, col) = if self.color(x + 1, y) == RED {
+                (RED, BLUE)
+            } else {
+                (BLUE, RED)
+            };
+            rgb[row] = self.convolve(x, y, &MHC_ROW);
+            rgb[col] = self.convolve(x, y, &MHC_COL);
+        } else {
+            rgb[GREEN] = self.convolve(x, y, &MHC_G);
+            rgb[RED + BLUE - color] = self.convolve(x, y, &MHC_DIAG);
+        }
+        rgb
+    }
+}
+
+/// Interpolates a full color frame from a raw Bayer frame.
+///
+/// # Panics
+///
+/// Panics if the dimensions of the source and destination frames differ.
+pub fn demosaic<T, const R: usize, const G: usize, const B: usize>(
+    src: ImageView<'_, Gray<T>, T>,
+    dst: &mut ImageViewMut<'_, Rgb<T, R, G, B>, T>,
+    pattern: CfaPattern,
+    method: Demosaic,
+) where
+    T: Sample,
+{
+    assert!(
+        src.width() == dst.width() && src.height() == dst.height(),
+        "frame dimensions do not match"
+    );
+
+    let raw = Raw {
+        frame: src,
+        width: src.width() as usize,
+        height: src.height() as usize,
+        pattern,
+    };
+
+    for y in 0..dst.height() {
+        let row = dst.row_mut(y).unwrap();
+        for (x, pix) in row.chunks_exact_mut(3).enumerate() {
+            let (x, y) = (x as isize, y as isize);
+            let rgb = match method {
+                Demosaic::Nearest => raw.nearest(x, y),
+                Demosaic::Bilinear => raw.bilinear(x, y),
+                Demosaic::Malvar => raw.malvar(x, y),
+            };
+
+            pix[R] = T::from_normalized(rgb[RED]);
+            pix[G] = T::from_normalized(rgb[GREEN]);
+            pix[B] = T::from_normalized(rgb[BLUE]);
+        }
+    }
+}
+
+/// Samples a full color frame through a color filter array, i.e. the inverse of [`demosaic`].
+///
+/// # Panics
+///
+/// Panics if the dimensions of the source and destination frames differ.
+pub fn mosaic<T, const R: usize, const G: usize, const B: usize>(
+    src: ImageView<'_, Rgb<T, R, G, B>, T>,
+    dst: &mut ImageViewMut<'_, Gray<T>, T>,
+    pattern: CfaPattern,
+) where
+    T: Copy,
+{
+    assert!(
+        src.width() == dst.width() && src.height() == dst.height(),
+        "frame dimensions do not match"
+    );
+
+    for (y, (src, dst)) in src.rows().zip(dst.rows_mut()).enumerate() {
+        for (x, (pix, raw)) in src.chunks_exact(3).zip(dst.iter_mut()).enumerate() {
+            *raw = match pattern.color(x, y) {
+                RED => pix[R],
+                GREEN => pix[G],
+                _ => pix[B],
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pattern() {
+        assert_eq!(CfaPattern::Rggb.color(0, 0), RED);
+        assert_eq!(CfaPattern::Rggb.color(3, 3), BLUE);
+        assert_eq!(CfaPattern::Bggr.color(2, 0), BLUE);
+        assert_eq!(CfaPattern::Grbg.color(1, 0), RED);
+        assert_eq!(CfaPattern::Gbrg.color(0, 1), RED);
+        assert_eq!(CfaPattern::Gbrg.color(1, 1), GREEN);
+    }
+
+    #[test]
+    fn mirror() {
+        assert_eq!(super::mirror(-2, 4), 2);
+        assert_eq!(super::mirror(-1, 4), 1);
+        assert_eq!(super::mirror(3, 4), 3);
+        assert_eq!(super::mirror(4, 4), 2);
+        assert_eq!(super::mirror(5, 4), 1);
+        assert_eq!(super::mirror(1, 1), 0);
+    }
+
+    #[test]
+    fn mosaic() {
+        let rgb = [1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let mut raw = [0u8; 4];
+
+        let src = ImageView::<Rgb<u8>, u8>::new(&rgb, 2, 2).unwrap();
+        let mut dst = ImageViewMut::<Gray<u8>, u8>::new(&mut raw, 2, 2).unwrap();
+        super::mosaic(src, &mut dst, CfaPattern::Rggb);
+        assert_eq!(raw, [1, 5, 8, 12]);
+
+        let mut dst = ImageViewMut::<Gray<u8>, u8>::new(&mut raw, 2, 2).unwrap();
+        super::mosaic(src, &mut dst, CfaPattern::Gbrg);
+        assert_eq!(raw, [2, 6, 7, 11]);
+    }
+
+    #[test]
+    fn flat() {
+        // a uniformly colored frame must be reconstructed exactly by all algorithms
+        let mut rgb = [0u16; 108];
+        rgb.chunks_exact_mut(3)
+            .for_each(|pix| pix.copy_from_slice(&[200, 100, 50]));
+        let mut raw = [0u16; 36];
+        let mut out = [0u16; 108];
+
+        for pattern in [
+            CfaPattern::Rggb,
+            CfaPattern::Bggr,
+            CfaPattern::Grbg,
+            CfaPattern::Gbrg,
+        ] {
+            let src = ImageView::<Rgb<u16>, u16>::new(&rgb, 6, 6).unwrap();
+            let mut dst = ImageViewMut::<Gray<u16>, u16>::new(&mut raw, 6, 6).unwrap();
+            super::mosaic(src, &mut dst, pattern);
+
+            for method in [Demosaic::Nearest, Demosaic::Bilinear, Demosaic::Malvar] {
+                let src = ImageView::<Gray<u16>, u16>::new(&raw, 6, 6).unwrap();
+                let mut dst = ImageViewMut::<Rgb<u16>, u16>::new(&mut out, 6, 6).unwrap();
+                demosaic(src, &mut dst, pattern, method);
+                assert_eq!(out, rgb);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn dimensions() {
+        let raw = [0u8; 4];
+        let mut out = [0u8; 6];
+
+        let src = ImageView::<Gray<u8>, u8>::new(&raw, 2, 2).unwrap();
+        let mut dst = ImageViewMut::<Rgb<u8>, u8>::new(&mut out, 2, 1).unwrap();
+        demosaic(src, &mut dst, CfaPattern::Rggb, Demosaic::Bilinear);
+    }
+}
@@ -0,0 +1,176 @@
+//! Zero-copy reinterpretation of sample buffers as pixels.
+//!
+//! Pixel types which are `#[repr(transparent)]` wrappers of their sample arrays implement
+//! [`Transparent`], so a buffer of samples (e.g. mapped from a driver) can be viewed and mutated
+//! as pixels in place:
+//!
+//! ```
+//! use ffimage::{cast, color::Rgb};
+//!
+//! let mut buf = [1u8, 2, 3, 4, 5, 6];
+//! let pixels = cast::as_pixels_mut::<Rgb<u8>>(&mut buf).unwrap();
+//! pixels[1] = Rgb([7, 8, 9]);
+//!
+//! assert_eq!(buf, [1, 2, 3, 7, 8, 9]);
+//! ```
+
+use core::{mem, slice};
+
+use crate::{
+    color::{Gray, GrayAlpha, Hsl, Hsv, Lab, LinearRgb, Luv, Rgb, Rgba, Xyz},
+    Error,
+};
+
+/// Pixel types which have the same memory layout as an array of their samples.
+///
+/// # Safety
+///
+/// Implementors must be `#[repr(transparent)]` wrappers of `[Self::Sample; N]` (apart from
+/// zero-sized fields) and any sample values must form a valid pixel. The size and alignment are
+/// checked at compile time when a cast is instantiated.
+pub unsafe trait Transparent: Copy {
+    /// Sample type
+    type Sample: Copy;
+
+    #[doc(hidden)]
+    const LAYOUT: () = assert!(
+        mem::size_of::<Self>() > 0
+            && mem::size_of::<Self>() % mem::size_of::<Self::Sample>() == 0
+            && mem::align_of::<Self>() == mem::align_of::<Self::Sample>(),
+        "pixel type is not a transparent wrapper of its sample array"
+    );
+}
+
+/// Implements [`Transparent`] for pixel types which wrap `[T; N]` and optional markers.
+macro_rules! transparent {
+    ($($name:ident<$($param:ident),*; $($c:ident: $ty:ty),*>),* $(,)?) => {$(
+        // SAFETY: transparent wrapper of [T; N] and zero-sized markers
+        unsafe impl<T: Copy, $($param,)* $(const $c: $ty),*> Transparent
+            for $name<T, $($param,)* $($c),*>
+        {
+            type Sample = T;
+        }
+    )*};
+}
+
+transparent!(
+    Gray<;>,
+    GrayAlpha<;>,
+    Rgb<; R: usize, G: usize, B: usize>,
+    Rgba<; R: usize, G: usize, B: usize, A: usize>,
+    Hsv<; HALF_HUE: bool>,
+    Hsl<; HALF_HUE: bool>,
+);
+
+// SAFETY: transparent wrappers of [T; 3] and PhantomData
+unsafe impl<T: Copy, W: Copy> Transparent for Xyz<T, W> {
+    type Sample = T;
+}
+unsafe impl<T: Copy, W: Copy> Transparent for Lab<T, W> {
+    type Sample = T;
+}
+unsafe impl<T: Copy, W: Copy> Transparent for Luv<T, W> {
+    type Sample = T;
+}
+unsafe impl<T: Copy, F: Copy> Transparent for LinearRgb<T, F> {
+    type Sample = T;
+}
+
+/// Returns the number of samples per pixel.
+fn channels<P: Transparent>() -> usize {
+    #[allow(clippy::let_unit_value)]
+    let () = P::LAYOUT;
+    mem::size_of::<P>() / mem::size_of::<P::Sample>()
+}
+
+/// Returns the number of pixels in a buffer of samples, which must hold whole pixels.
+fn pixel_count<P: Transparent>(len: usize) -> Result<usize, Error> {
+    let channels = channels::<P>();
+    if len % channels != 0 {
+        return Err(Error::PartialPixel {
+            channels,
+            actual: len,
+        });
+    }
+
+    Ok(len / channels)
+}
+
+/// Reinterprets a buffer of samples as pixels.
+///
+/// Fails if the buffer does not hold whole pixels. The alignment always fits, since a pixel is
+/// aligned like its samples.
+pub fn as_pixels<P: Transparent>(samples: &[P::Sample]) -> Result<&[P], Error> {
+    let len = pixel_count::<P>(samples.len())?;
+    // SAFETY: P is a transparent wrapper of [P::Sample; N]
+    Ok(unsafe { slice::from_raw_parts(samples.as_ptr() as *const P, len) })
+}
+
+/// Reinterprets a mutable buffer of samples as pixels.
+///
+/// Fails if the buffer does not hold whole pixels.
+pub fn as_pixels_mut<P: Transparent>(samples: &mut [P::Sample]) -> Result<&mut [P], Error> {
+    let len = pixel_count::<P>(samples.len())?;
+    // SAFETY: P is a transparent wrapper of [P::Sample; N]
+    Ok(unsafe { slice::from_raw_parts_mut(samples.as_mut_ptr() as *mut P, len) })
+}
+
+/// Reinterprets pixels as a buffer of samples.
+pub fn as_bytes<P: Transparent>(pixels: &[P]) -> &[P::Sample] {
+    let len = pixels.len() * channels::<P>();
+    // SAFETY: P is a transparent wrapper of [P::Sample; N]
+    unsafe { slice::from_raw_parts(pixels.as_ptr() as *const P::Sample, len) }
+}
+
+/// Reinterprets mutable pixels as a buffer of samples.
+pub fn as_bytes_mut<P: Transparent>(pixels: &mut [P]) -> &mut [P::Sample] {
+    let len = pixels.len() * channels::<P>();
+    // SAFETY: P is a transparent wrapper of [P::Sample; N]
+    unsafe { slice::from_raw_parts_mut(pixels.as_mut_ptr() as *mut P::Sample, len) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Rgb565;
+
+    #[test]
+    fn pixels() {
+        let buf = [1u16, 2, 3, 4, 5, 6, 7, 8];
+
+        assert_eq!(
+            as_pixels::<Rgba<u16>>(&buf).unwrap(),
+            [Rgba([1, 2, 3, 4]), Rgba([5, 6, 7, 8])]
+        );
+        assert_eq!(as_pixels::<Gray<u16>>(&buf).unwrap().len(), 8);
+        assert_eq!(
+            as_pixels::<Rgb<u16>>(&buf),
+            Err(Error::PartialPixel {
+                channels: 3,
+                actual: 8
+            })
+        );
+
+        let xyz = as_pixels::<Xyz<f32>>(&[0.25, 0.5, 0.75]).unwrap();
+        assert_eq!(xyz, [Xyz::from([0.25, 0.5, 0.75])]);
+    }
+
+    #[test]
+    fn pixels_mut() {
+        let mut buf = [0u8; 4];
+
+        let pixels = as_pixels_mut::<Rgb565>(&mut buf).unwrap();
+        pixels[0] = Rgb565::from(0x1234u16);
+        pixels[1] = Rgb565::from([0xab, 0xcd]);
+        assert_eq!(buf, [0x34, 0x12, 0xab, 0xcd]);
+    }
+
+    #[test]
+    fn bytes() {
+        let mut pixels = [Rgb::<u8>([1, 2, 3]), Rgb([4, 5, 6])];
+        assert_eq!(as_bytes(&pixels), [1, 2, 3, 4, 5, 6]);
+
+        as_bytes_mut(&mut pixels)[5] = 7;
+        assert_eq!(pixels[1], Rgb([4, 5, 7]));
+    }
+}
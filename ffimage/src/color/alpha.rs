@@ -0,0 +1,227 @@
+use crate::color::{gray::Gray, gray_alpha::GrayAlpha, rgb::Rgb, rgba::Rgba};
+
+/// Sample types which can be used for alpha compositing.
+///
+/// Integer samples are interpreted as fractions of their maximum value (e.g. 255 means 1.0 for
+/// `u8`), float samples are expected to be in the range of 0.0 to 1.0. Integer results are
+/// rounded to the nearest code value.
+pub trait AlphaSample: Copy {
+    /// Returns the sample multiplied by alpha.
+    fn mul_alpha(self, alpha: Self) -> Self;
+
+    /// Returns the sample divided by alpha, i.e. the inverse of [`AlphaSample::mul_alpha`].
+    ///
+    /// Samples are clamped to the valid range, the result is zero for a zero alpha value.
+    fn div_alpha(self, alpha: Self) -> Self;
+
+    /// Returns the sample blended over a background sample, i.e. `self * alpha + background *
+    /// (1 - alpha)`.
+    fn blend(self, background: Self, alpha: Self) -> Self;
+}
+
+impl AlphaSample for u8 {
+    fn mul_alpha(self, alpha: Self) -> Self {
+        div_255(self as u32 * alpha as u32)
+    }
+
+    fn div_alpha(self, alpha: Self) -> Self {
+        if alpha == 0 {
+            return 0;
+        }
+
+        let alpha = alpha as u32;
+        ((self as u32 * 255 + alpha / 2) / alpha).min(255) as u8
+    }
+
+    fn blend(self, background: Self, alpha: Self) -> Self {
+        div_255(self as u32 * alpha as u32 + background as u32 * (255 - alpha as u32))
+    }
+}
+
+impl AlphaSample for u16 {
+    fn mul_alpha(self, alpha: Self) -> Self {
+        div_65535(self as u32 * alpha as u32)
+    }
+
+    fn div_alpha(self, alpha: Self) -> Self {
+        if alpha == 0 {
+            return 0;
+        }
+
+        let alpha = alpha as u64;
+        ((self as u64 * 65535 + alpha / 2) / alpha).min(65535) as u16
+    }
+
+    fn blend(self, background: Self, alpha: Self) -> Self {
+        div_65535(self as u32 * alpha as u32 + background as u32 * (65535 - alpha as u32))
+    }
+}
+
+impl AlphaSample for f32 {
+    fn mul_alpha(self, alpha: Self) -> Self {
+        self * alpha
+    }
+
+    fn div_alpha(self, alpha: Self) -> Self {
+        if alpha == 0.0 {
+            return 0.0;
+        }
+
+        (self / alpha).min(1.0)
+    }
+
+    fn blend(self, background: Self, alpha: Self) -> Self {
+        self * alpha + background * (1.0 - alpha)
+    }
+}
+
+#[cfg(feature = "f16")]
+impl AlphaSample for crate::sample::f16 {
+    fn mul_alpha(self, alpha: Self) -> Self {
+        Self::from_f32(self.to_f32().mul_alpha(alpha.to_f32()))
+    }
+
+    fn div_alpha(self, alpha: Self) -> Self {
+        Self::from_f32(self.to_f32().div_alpha(alpha.to_f32()))
+    }
+
+    fn blend(self, background: Self, alpha: Self) -> Self {
+        Self::from_f32(self.to_f32().blend(background.to_f32(), alpha.to_f32()))
+    }
+}
+
+/// Returns `x / 255`, rounded to the nearest integer, for `x <= 255 * 255`.
+fn div_255(x: u32) -> u8 {
+    let x = x + 128;
+    ((x + (x >> 8)) >> 8) as u8
+}
+
+/// Returns `x / 65535`, rounded to the nearest integer, for `x <= 65535 * 65535`.
+fn div_65535(x: u32) -> u16 {
+    let x = x + 32768;
+    ((x + (x >> 16)) >> 16) as u16
+}
+
+/// Pixels which carry an alpha channel.
+///
+/// Pixels are expected to have straight (i.e. not premultiplied) alpha unless they have been
+/// premultiplied explicitly.
+pub trait Alpha {
+    /// Pixel type without an alpha channel
+    type Opaque;
+
+    /// Returns the pixel with its color channels multiplied by alpha.
+    fn premultiply(self) -> Self;
+
+    /// Returns the pixel with its color channels divided by alpha.
+    fn unpremultiply(self) -> Self;
+
+    /// Returns the pixel composited over a background color, dropping the alpha channel.
+    fn flatten(self, background: Self::Opaque) -> Self::Opaque;
+}
+
+impl<T, const R: usize, const G: usize, const B: usize, const A: usize> Alpha
+    for Rgba<T, R, G, B, A>
+where
+    T: AlphaSample,
+{
+    type Opaque = Rgb<T>;
+
+    fn premultiply(mut self) -> Self {
+        let alpha = self[A];
+        self[R] = self[R].mul_alpha(alpha);
+        self[G] = self[G].mul_alpha(alpha);
+        self[B] = self[B].mul_alpha(alpha);
+        self
+    }
+
+    fn unpremultiply(mut self) -> Self {
+        let alpha = self[A];
+        self[R] = self[R].div_alpha(alpha);
+        self[G] = self[G].div_alpha(alpha);
+        self[B] = self[B].div_alpha(alpha);
+        self
+    }
+
+    fn flatten(self, background: Self::Opaque) -> Self::Opaque {
+        let alpha = self[A];
+        Rgb([
+            self[R].blend(background[0], alpha),
+            self[G].blend(background[1], alpha),
+            self[B].blend(background[2], alpha),
+        ])
+    }
+}
+
+impl<T> Alpha for GrayAlpha<T>
+where
+    T: AlphaSample,
+{
+    type Opaque = Gray<T>;
+
+    fn premultiply(self) -> Self {
+        GrayAlpha([self[0].mul_alpha(self[1]), self[1]])
+    }
+
+    fn unpremultiply(self) -> Self {
+        GrayAlpha([self[0].div_alpha(self[1]), self[1]])
+    }
+
+    fn flatten(self, background: Self::Opaque) -> Self::Opaque {
+        Gray([self[0].blend(background[0], self[1])])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Argb;
+
+    #[test]
+    fn mul_alpha() {
+        for c in 0..=255u8 {
+            for a in 0..=255u8 {
+                let expected = (c as f32 * a as f32 / 255.0).round() as u8;
+                assert_eq!(c.mul_alpha(a), expected);
+            }
+        }
+
+        assert_eq!(u16::MAX.mul_alpha(u16::MAX), u16::MAX);
+        assert_eq!(1000u16.mul_alpha(32768), 500);
+    }
+
+    #[test]
+    fn div_alpha() {
+        for c in 0..=255u8 {
+            for a in 1..=255u8 {
+                assert!(c.mul_alpha(a).div_alpha(a).abs_diff(c) <= 255 / a / 2 + 1);
+            }
+        }
+
+        assert_eq!(10u8.div_alpha(0), 0);
+        assert_eq!(200u8.div_alpha(100), 255);
+    }
+
+    #[test]
+    fn premultiply() {
+        let argb = Argb::<u8>::from([128, 255, 100, 0]);
+
+        assert_eq!(argb.premultiply(), Rgba([128, 128, 50, 0]));
+        assert_eq!(argb.premultiply().unpremultiply(), argb);
+        assert_eq!(
+            GrayAlpha::<f32>([0.5, 0.5]).premultiply(),
+            GrayAlpha([0.25, 0.5])
+        );
+    }
+
+    #[test]
+    fn flatten() {
+        let rgba = Rgba::<u8>([255, 0, 100, 128]);
+
+        assert_eq!(rgba.flatten(Rgb([0, 255, 100])), Rgb([128, 127, 100]));
+        assert_eq!(
+            GrayAlpha::<u16>([u16::MAX, 0]).flatten(Gray([7])),
+            Gray([7])
+        );
+    }
+}
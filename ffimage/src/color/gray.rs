@@ -1,11 +1,14 @@
 use core::ops::{Deref, DerefMut};
 
-use num_traits::FromPrimitive;
-
-use crate::{color::rgb::Rgb, Pixel};
+use crate::{
+    color::rgb::Rgb,
+    sample::{FromDepth, Sample},
+    Pixel,
+};
 
 /// Grayscale pixel
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
 pub struct Gray<T>(pub [T; 1]);
 
 impl<T> From<[T; 1]> for Gray<T> {
@@ -32,18 +35,190 @@ impl<T> Pixel for Gray<T> {
     const CHANNELS: u8 = 1;
 }
 
+impl<T, U> FromDepth<Gray<U>> for Gray<T>
+where
+    T: Sample,
+    U: Sample,
+{
+    fn from_depth(gray: Gray<U>) -> Self {
+        Gray([gray[0].to_sample()])
+    }
+}
+
+/// Luma coefficients for deriving grayscale values from RGB.
+///
+/// The luma value is computed as the weighted sum `KR * R + KG * G + KB * B`. Converting via
+/// `From<Rgb>` uses the [`Bt709`] coefficients, pick another standard by calling
+/// [`Luma::luma`] directly:
+///
+/// ```
+/// use ffimage::color::{Bt601, Gray, Luma, Rgb};
+///
+/// let gray: Gray<u8> = Bt601::luma(Rgb::<u8>([255, 0, 0]));
+/// assert_eq!(gray, Gray([76]));
+/// ```
+pub trait Luma: Sized {
+    /// Weight of the red channel
+    const KR: f32;
+    /// Weight of the green channel
+    const KG: f32;
+    /// Weight of the blue channel
+    const KB: f32;
+
+    /// Weight of the red channel as 16.16 fixed-point number
+    const KR_FIXED: u32 = (Self::KR * 65536.0 + 0.5) as u32;
+    /// Weight of the green channel as 16.16 fixed-point number
+    const KG_FIXED: u32 = (Self::KG * 65536.0 + 0.5) as u32;
+    /// Weight of the blue channel as 16.16 fixed-point number
+    ///
+    /// The weights must add up to exactly one, so white is preserved.
+    const KB_FIXED: u32 = 65536 - Self::KR_FIXED - Self::KG_FIXED;
+
+    /// Weight of the red channel as 32.32 fixed-point number
+    ///
+    /// 16 bit fractions are not precise enough for 16 bit samples.
+    const KR_FIXED32: u64 = (Self::KR as f64 * 4294967296.0 + 0.5) as u64;
+    /// Weight of the green channel as 32.32 fixed-point number
+    const KG_FIXED32: u64 = (Self::KG as f64 * 4294967296.0 + 0.5) as u64;
+    /// Weight of the blue channel as 32.32 fixed-point number
+    const KB_FIXED32: u64 = (1 << 32) - Self::KR_FIXED32 - Self::KG_FIXED32;
+
+    /// Returns the grayscale representation of a RGB pixel.
+    fn luma<T, const R: usize, const G: usize, const B: usize>(rgb: Rgb<T, R, G, B>) -> Gray<T>
+    where
+        T: LumaSample,
+    {
+        Gray([T::luma::<Self>(rgb[R], rgb[G], rgb[B])])
+    }
+}
+
+/// ITU-R BT.601 (SDTV) luma coefficients
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bt601;
+
+impl Luma for Bt601 {
+    const KR: f32 = 0.299;
+    const KG: f32 = 0.587;
+    const KB: f32 = 0.114;
+}
+
+/// ITU-R BT.709 (HDTV) luma coefficients
+///
+/// See [`crate::color::Bt709Oetf`] for the BT.709 transfer function.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bt709;
+
+impl Luma for Bt709 {
+    const KR: f32 = 0.2126;
+    const KG: f32 = 0.7152;
+    const KB: f32 = 0.0722;
+}
+
+/// Luma coefficients of the `From<Rgb>` implementation of [`Gray`]
+///
+/// Specialized kernels for that conversion (e.g. [`crate::convert::SimdKernel`]) use the same
+/// coefficients.
+pub type DefaultLuma = Bt709;
+
+/// ITU-R BT.2020 (UHDTV) luma coefficients
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bt2020;
+
+impl Luma for Bt2020 {
+    const KR: f32 = 0.2627;
+    const KG: f32 = 0.6780;
+    const KB: f32 = 0.0593;
+}
+
+/// Equally weighted channels, i.e. the plain average of R, G and B
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Average;
+
+impl Luma for Average {
+    const KR: f32 = 1.0 / 3.0;
+    const KG: f32 = 1.0 / 3.0;
+    const KB: f32 = 1.0 / 3.0;
+}
+
+/// Sample types which luma values can be computed for.
+///
+/// The luma value is computed from channels of type `U`, which defaults to the sample type
+/// itself. Integer samples of the same type use fixed-point arithmetic, the result is rounded to
+/// the nearest code value and thus within one code value of the floating point computation.
+/// Between different primitive sample types, the weighted sum is computed in floating point and
+/// converted with `as` semantics, i.e. truncated and saturated for integer samples.
+pub trait LumaSample<U = Self>: Copy {
+    /// Returns the luma value of the given channels.
+    fn luma<L: Luma>(r: U, g: U, b: U) -> Self;
+}
+
+impl LumaSample for u8 {
+    fn luma<L: Luma>(r: Self, g: Self, b: Self) -> Self {
+        let y = L::KR_FIXED * r as u32 + L::KG_FIXED * g as u32 + L::KB_FIXED * b as u32;
+        ((y + (1 << 15)) >> 16) as u8
+    }
+}
+
+impl LumaSample for u16 {
+    fn luma<L: Luma>(r: Self, g: Self, b: Self) -> Self {
+        let y = L::KR_FIXED32 * r as u64 + L::KG_FIXED32 * g as u64 + L::KB_FIXED32 * b as u64;
+        ((y + (1 << 31)) >> 32) as u16
+    }
+}
+
+impl LumaSample for f32 {
+    fn luma<L: Luma>(r: Self, g: Self, b: Self) -> Self {
+        L::KR * r + L::KG * g + L::KB * b
+    }
+}
+
+impl LumaSample for f64 {
+    fn luma<L: Luma>(r: Self, g: Self, b: Self) -> Self {
+        L::KR as f64 * r + L::KG as f64 * g + L::KB as f64 * b
+    }
+}
+
+#[cfg(feature = "f16")]
+impl LumaSample for crate::sample::f16 {
+    fn luma<L: Luma>(r: Self, g: Self, b: Self) -> Self {
+        Self::from_f32(f32::luma::<L>(r.to_f32(), g.to_f32(), b.to_f32()))
+    }
+}
+
+macro_rules! impl_luma_sample {
+    ($t:ty => $($u:ty),+) => {
+        $(
+            impl LumaSample<$u> for $t {
+                fn luma<L: Luma>(r: $u, g: $u, b: $u) -> Self {
+                    f64::luma::<L>(r as f64, g as f64, b as f64) as $t
+                }
+            }
+        )+
+    };
+}
+
+impl_luma_sample!(u8 => u16, u32, i8, i16, i32, f32, f64);
+impl_luma_sample!(u16 => u8, u32, i8, i16, i32, f32, f64);
+impl_luma_sample!(u32 => u8, u16, u32, i8, i16, i32, f32, f64);
+impl_luma_sample!(i8 => u8, u16, u32, i8, i16, i32, f32, f64);
+impl_luma_sample!(i16 => u8, u16, u32, i8, i16, i32, f32, f64);
+impl_luma_sample!(i32 => u8, u16, u32, i8, i16, i32, f32, f64);
+impl_luma_sample!(f32 => u8, u16, u32, i8, i16, i32, f64);
+impl_luma_sample!(f64 => u8, u16, u32, i8, i16, i32, f32);
+impl_luma_sample!(u64 => u8, u16, u32, i8, i16, i32, f32, f64);
+impl_luma_sample!(u128 => u8, u16, u32, i8, i16, i32, f32, f64);
+impl_luma_sample!(usize => u8, u16, u32, i8, i16, i32, f32, f64);
+impl_luma_sample!(i64 => u8, u16, u32, i8, i16, i32, f32, f64);
+impl_luma_sample!(i128 => u8, u16, u32, i8, i16, i32, f32, f64);
+impl_luma_sample!(isize => u8, u16, u32, i8, i16, i32, f32, f64);
+
 impl<T, U, const R: usize, const G: usize, const B: usize> From<Rgb<U, R, G, B>> for Gray<T>
 where
-    T: Copy + FromPrimitive,
-    U: Copy + Into<f32>,
+    T: LumaSample<U>,
+    U: Copy,
 {
     fn from(rgb: Rgb<U, R, G, B>) -> Self {
-        // rec601 luma
-        let y =
-            T::from_f32(0.2126 * rgb[R].into() + 0.7152 * rgb[G].into() + 0.0722 * rgb[B].into())
-                .expect("could not convert from f32 to T");
-
-        Gray([y])
+        Gray([T::luma::<DefaultLuma>(rgb[R], rgb[G], rgb[B])])
     }
 }
 
@@ -66,9 +241,49 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::init_numbered_fields)]
     fn index_mut() {
         let pix: Gray<u8> = Gray { 0: [1] };
 
         assert_eq!(pix[0], 1);
     }
+
+    #[test]
+    fn luma() {
+        let rgb = Rgb::<u8>([200, 100, 50]);
+
+        assert_eq!(Bt601::luma(rgb), Gray::<u8>([124]));
+        assert_eq!(Bt709::luma(rgb), Gray::<u8>([118]));
+        assert_eq!(Bt2020::luma(rgb), Gray::<u8>([123]));
+        assert_eq!(Average::luma(rgb), Gray::<u8>([117]));
+        assert_eq!(Gray::<u8>::from(rgb), Bt709::luma(rgb));
+    }
+
+    #[test]
+    fn luma_fixed() {
+        fn check<L: Luma>() {
+            assert_eq!(L::KR_FIXED + L::KG_FIXED + L::KB_FIXED, 1 << 16);
+            assert_eq!(L::luma(Rgb::<u8>([u8::MAX; 3])), Gray([u8::MAX]));
+            assert_eq!(L::luma(Rgb::<u16>([u16::MAX; 3])), Gray([u16::MAX]));
+        }
+
+        check::<Bt601>();
+        check::<Bt709>();
+        check::<Bt2020>();
+        check::<Average>();
+    }
+
+    #[test]
+    fn luma_cross() {
+        let rgb = Rgb::<u8>([200, 100, 50]);
+
+        let gray = Gray::<f32>::from(rgb);
+        assert!((gray[0] - 117.65).abs() < 1e-3);
+        assert_eq!(Gray::<u16>::from(rgb), Gray([117]));
+        assert_eq!(Gray::<u8>::from(Rgb::<u16>([1000; 3])), Gray([255]));
+        assert_eq!(Gray::<i32>::from(Rgb::<f64>([-2.0; 3])), Gray([-2]));
+        assert_eq!(Gray::<u64>::from(rgb), Gray([117]));
+        assert_eq!(Gray::<i64>::from(Rgb::<i16>([-2; 3])), Gray([-2]));
+        assert_eq!(Gray::<usize>::from(Rgb::<f32>([3.0; 3])), Gray([3]));
+    }
 }
@@ -0,0 +1,172 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::{
+    color::{
+        gray::{Gray, LumaSample},
+        rgb::Rgb,
+        rgba::Rgba,
+    },
+    sample::{FromDepth, Sample},
+    Pixel,
+};
+
+/// Grayscale pixel with alpha channel
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct GrayAlpha<T>(pub [T; 2]);
+
+impl<T> From<[T; 2]> for GrayAlpha<T> {
+    fn from(value: [T; 2]) -> Self {
+        GrayAlpha(value)
+    }
+}
+
+impl<T> Deref for GrayAlpha<T> {
+    type Target = [T; 2];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for GrayAlpha<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Pixel for GrayAlpha<T> {
+    const CHANNELS: u8 = 2;
+}
+
+impl<T> From<GrayAlpha<T>> for Gray<T>
+where
+    T: Copy,
+{
+    fn from(gray: GrayAlpha<T>) -> Self {
+        // the alpha channel is dropped
+        Gray([gray[0]])
+    }
+}
+
+impl<T, const R: usize, const G: usize, const B: usize> From<GrayAlpha<T>> for Rgb<T, R, G, B>
+where
+    T: Copy,
+{
+    fn from(gray: GrayAlpha<T>) -> Self {
+        // the alpha channel is dropped
+        Rgb([gray[0], gray[0], gray[0]])
+    }
+}
+
+impl<T, const R: usize, const G: usize, const B: usize, const A: usize> From<GrayAlpha<T>>
+    for Rgba<T, R, G, B, A>
+where
+    T: Copy + Default,
+{
+    fn from(gray: GrayAlpha<T>) -> Self {
+        let mut rgba = Rgba::<T, R, G, B, A>::default();
+        rgba[R] = gray[0];
+        rgba[G] = gray[0];
+        rgba[B] = gray[0];
+        rgba[A] = gray[1];
+        rgba
+    }
+}
+
+impl<T, const R: usize, const G: usize, const B: usize, const A: usize> From<Rgba<T, R, G, B, A>>
+    for GrayAlpha<T>
+where
+    T: LumaSample,
+{
+    fn from(rgba: Rgba<T, R, G, B, A>) -> Self {
+        let gray = Gray::from(Rgb::<T>([rgba[R], rgba[G], rgba[B]]));
+        GrayAlpha([gray[0], rgba[A]])
+    }
+}
+
+impl<T> From<Gray<T>> for GrayAlpha<T>
+where
+    T: Sample,
+{
+    fn from(gray: Gray<T>) -> Self {
+        GrayAlpha([gray[0], T::MAX])
+    }
+}
+
+impl<T, const R: usize, const G: usize, const B: usize> From<Rgb<T, R, G, B>> for GrayAlpha<T>
+where
+    T: LumaSample + Sample,
+{
+    fn from(rgb: Rgb<T, R, G, B>) -> Self {
+        let gray = Gray::from(rgb);
+        GrayAlpha([gray[0], T::MAX])
+    }
+}
+
+impl<T, U> FromDepth<GrayAlpha<U>> for GrayAlpha<T>
+where
+    T: Sample,
+    U: Sample,
+{
+    fn from_depth(gray: GrayAlpha<U>) -> Self {
+        GrayAlpha([gray[0].to_sample(), gray[1].to_sample()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::{Bgr, Bgra},
+        iter::{BytesExt, ColorConvertExt, PixelsExt},
+    };
+
+    #[test]
+    fn channels() {
+        assert_eq!(GrayAlpha::<u8>::CHANNELS, 2);
+    }
+
+    #[test]
+    fn index_mut() {
+        let pix: GrayAlpha<u8> = GrayAlpha([1, 2]);
+
+        assert_eq!(pix[0], 1);
+        assert_eq!(pix[1], 2);
+    }
+
+    #[test]
+    fn gray() {
+        assert_eq!(GrayAlpha::<u8>::from(Gray([7])), GrayAlpha([7, 255]));
+        assert_eq!(Gray::<u8>::from(GrayAlpha([7, 128])), Gray([7]));
+    }
+
+    #[test]
+    fn rgb() {
+        let bgr: Bgr<u16> = Rgb([10, 10, 10]);
+
+        assert_eq!(GrayAlpha::<u16>::from(bgr), GrayAlpha([10, u16::MAX]));
+        assert_eq!(Bgr::<u16>::from(GrayAlpha([10, 0])), bgr);
+    }
+
+    #[test]
+    fn rgba() {
+        let bgra = Bgra::<u8>::from([10, 10, 10, 128]);
+
+        assert_eq!(GrayAlpha::<u8>::from(bgra), GrayAlpha([10, 128]));
+        assert_eq!(Bgra::<u8>::from(GrayAlpha([10, 128])), bgra);
+    }
+
+    #[test]
+    fn iter() {
+        let buf = [1, 2, 3, 4];
+        let mut out = [0; 8];
+        buf.iter()
+            .copied()
+            .pixels::<GrayAlpha<u8>>()
+            .colorconvert::<Rgba<u8>>()
+            .bytes()
+            .write(&mut out);
+        assert_eq!(out, [1, 1, 1, 2, 3, 3, 3, 4]);
+    }
+}
@@ -0,0 +1,134 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::{
+    color::{
+        hsv::{hue, rgb, HueSample},
+        rgb::Rgb,
+    },
+    Pixel,
+};
+
+/// HSL pixel
+///
+/// Saturation and lightness use the normalized range of the sample type (see
+/// [`crate::sample::Sample`]). The hue channel depends on the sample type, see [`HueSample`]. If
+/// `HALF_HUE` is set, integer hue values are stored as half of the angle (i.e. 0..180) just like
+/// OpenCV does.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Hsl<T, const HALF_HUE: bool = false>(pub [T; 3]);
+
+/// HSL pixel with OpenCV style hue (0..180 for `u8`)
+pub type Hsl180<T> = Hsl<T, true>;
+
+impl<T, const HALF_HUE: bool> From<[T; 3]> for Hsl<T, HALF_HUE> {
+    fn from(value: [T; 3]) -> Self {
+        Hsl(value)
+    }
+}
+
+impl<T, const HALF_HUE: bool> Deref for Hsl<T, HALF_HUE> {
+    type Target = [T; 3];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const HALF_HUE: bool> DerefMut for Hsl<T, HALF_HUE> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, const HALF_HUE: bool> Pixel for Hsl<T, HALF_HUE> {
+    const CHANNELS: u8 = 3;
+}
+
+impl<T, const HALF_HUE: bool, const R: usize, const G: usize, const B: usize> From<Rgb<T, R, G, B>>
+    for Hsl<T, HALF_HUE>
+where
+    T: HueSample,
+{
+    fn from(rgb: Rgb<T, R, G, B>) -> Self {
+        let r = rgb[R].to_normalized();
+        let g = rgb[G].to_normalized();
+        let b = rgb[B].to_normalized();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+
+        let h = hue(r, g, b, max, chroma);
+        let l = (max + min) / 2.0;
+        let s = if chroma == 0.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * l - 1.0).max(1.0 - 2.0 * l))
+        };
+
+        Hsl([
+            T::from_degrees(h, HALF_HUE),
+            T::from_normalized(s),
+            T::from_normalized(l),
+        ])
+    }
+}
+
+impl<T, const HALF_HUE: bool, const R: usize, const G: usize, const B: usize> From<Hsl<T, HALF_HUE>>
+    for Rgb<T, R, G, B>
+where
+    T: HueSample + Default,
+{
+    fn from(hsl: Hsl<T, HALF_HUE>) -> Self {
+        let h = hsl[0].to_degrees(HALF_HUE);
+        let s = hsl[1].to_normalized();
+        let l = hsl[2].to_normalized();
+        let chroma = (1.0 - (2.0 * l - 1.0).max(1.0 - 2.0 * l)) * s;
+
+        let [r, g, b] = rgb(h, chroma, l - chroma / 2.0);
+
+        let mut rgb = Rgb::<T, R, G, B>::default();
+        rgb[R] = T::from_normalized(r);
+        rgb[G] = T::from_normalized(g);
+        rgb[B] = T::from_normalized(b);
+        rgb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels() {
+        assert_eq!(Hsl::<u8>::CHANNELS, 3);
+    }
+
+    #[test]
+    fn from_rgb() {
+        assert_eq!(
+            Hsl::<f32>::from(Rgb::<f32>([1.0, 0.0, 0.0])),
+            Hsl([0.0, 1.0, 0.5])
+        );
+        assert_eq!(
+            Hsl::<f32>::from(Rgb::<f32>([1.0, 1.0, 1.0])),
+            Hsl([0.0, 0.0, 1.0])
+        );
+        assert_eq!(
+            Hsl180::<u8>::from(Rgb::<u8>([0, 0, 255])),
+            Hsl([120, 255, 128])
+        );
+    }
+
+    #[test]
+    fn to_rgb() {
+        assert_eq!(
+            Rgb::<u8>::from(Hsl::<u8>([0, 0, 128])),
+            Rgb([128, 128, 128])
+        );
+        assert_eq!(
+            Rgb::<u8>::from(Hsl180::<u8>::from([60, 255, 128])),
+            Rgb([1, 255, 1])
+        );
+    }
+}
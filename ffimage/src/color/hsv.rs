@@ -0,0 +1,230 @@
+use core::ops::{Deref, DerefMut};
+
+use num_traits::{float::FloatCore, Euclid};
+
+use crate::{color::rgb::Rgb, sample::Sample, Pixel};
+
+/// HSV pixel
+///
+/// Saturation and value use the normalized range of the sample type (see [`Sample`]). The hue
+/// channel depends on the sample type, see [`HueSample`]. If `HALF_HUE` is set, integer hue
+/// values are stored as half of the angle (i.e. 0..180) just like OpenCV does.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Hsv<T, const HALF_HUE: bool = false>(pub [T; 3]);
+
+/// HSV pixel with OpenCV style hue (0..180 for `u8`)
+pub type Hsv180<T> = Hsv<T, true>;
+
+impl<T, const HALF_HUE: bool> From<[T; 3]> for Hsv<T, HALF_HUE> {
+    fn from(value: [T; 3]) -> Self {
+        Hsv(value)
+    }
+}
+
+impl<T, const HALF_HUE: bool> Deref for Hsv<T, HALF_HUE> {
+    type Target = [T; 3];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const HALF_HUE: bool> DerefMut for Hsv<T, HALF_HUE> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, const HALF_HUE: bool> Pixel for Hsv<T, HALF_HUE> {
+    const CHANNELS: u8 = 3;
+}
+
+/// Sample types which can represent hue angles.
+///
+/// Float samples hold the angle in degrees (0.0..360.0). Integer samples map the full circle to
+/// their value range, e.g. 0..=255 for `u8`, or hold half of the angle (0..180) if requested.
+pub trait HueSample: Sample {
+    /// Returns the hue sample for an angle in degrees.
+    fn from_degrees(degrees: f32, half: bool) -> Self;
+
+    /// Returns the angle in degrees.
+    fn to_degrees(self, half: bool) -> f32;
+}
+
+impl HueSample for u8 {
+    fn from_degrees(degrees: f32, half: bool) -> Self {
+        if half {
+            let hue = (degrees / 2.0 + 0.5) as u32;
+            (hue % 180) as u8
+        } else {
+            let hue = (degrees / 360.0 * 256.0 + 0.5) as u32;
+            (hue % 256) as u8
+        }
+    }
+
+    fn to_degrees(self, half: bool) -> f32 {
+        if half {
+            self as f32 * 2.0
+        } else {
+            self as f32 / 256.0 * 360.0
+        }
+    }
+}
+
+impl HueSample for f32 {
+    fn from_degrees(degrees: f32, _half: bool) -> Self {
+        degrees
+    }
+
+    fn to_degrees(self, _half: bool) -> f32 {
+        self
+    }
+}
+
+/// Returns the hue in degrees (0.0..360.0) of normalized RGB values.
+pub(crate) fn hue(r: f32, g: f32, b: f32, max: f32, chroma: f32) -> f32 {
+    if chroma == 0.0 {
+        return 0.0;
+    }
+
+    let hue = if max == r {
+        (g - b) / chroma
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    } * 60.0;
+
+    if hue < 0.0 {
+        hue + 360.0
+    } else {
+        hue
+    }
+}
+
+/// Returns normalized RGB values for a hue in degrees, chroma and the lightness offset.
+pub(crate) fn rgb(hue: f32, chroma: f32, m: f32) -> [f32; 3] {
+    // hues outside of 0.0..360.0 wrap around
+    let sector = Euclid::rem_euclid(&hue, &360.0) / 60.0;
+    let x = chroma * (1.0 - FloatCore::abs(Euclid::rem_euclid(&sector, &2.0) - 1.0));
+
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    [r + m, g + m, b + m]
+}
+
+impl<T, const HALF_HUE: bool, const R: usize, const G: usize, const B: usize> From<Rgb<T, R, G, B>>
+    for Hsv<T, HALF_HUE>
+where
+    T: HueSample,
+{
+    fn from(rgb: Rgb<T, R, G, B>) -> Self {
+        let r = rgb[R].to_normalized();
+        let g = rgb[G].to_normalized();
+        let b = rgb[B].to_normalized();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+
+        let h = hue(r, g, b, max, chroma);
+        let s = if max == 0.0 { 0.0 } else { chroma / max };
+
+        Hsv([
+            T::from_degrees(h, HALF_HUE),
+            T::from_normalized(s),
+            T::from_normalized(max),
+        ])
+    }
+}
+
+impl<T, const HALF_HUE: bool, const R: usize, const G: usize, const B: usize> From<Hsv<T, HALF_HUE>>
+    for Rgb<T, R, G, B>
+where
+    T: HueSample + Default,
+{
+    fn from(hsv: Hsv<T, HALF_HUE>) -> Self {
+        let h = hsv[0].to_degrees(HALF_HUE);
+        let s = hsv[1].to_normalized();
+        let v = hsv[2].to_normalized();
+        let chroma = v * s;
+
+        let [r, g, b] = rgb(h, chroma, v - chroma);
+
+        let mut rgb = Rgb::<T, R, G, B>::default();
+        rgb[R] = T::from_normalized(r);
+        rgb[G] = T::from_normalized(g);
+        rgb[B] = T::from_normalized(b);
+        rgb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels() {
+        assert_eq!(Hsv::<u8>::CHANNELS, 3);
+    }
+
+    #[test]
+    fn from_rgb() {
+        assert_eq!(
+            Hsv::<f32>::from(Rgb::<f32>([1.0, 0.0, 0.0])),
+            Hsv([0.0, 1.0, 1.0])
+        );
+        assert_eq!(
+            Hsv::<f32>::from(Rgb::<f32>([0.0, 0.5, 0.0])),
+            Hsv([120.0, 1.0, 0.5])
+        );
+        assert_eq!(
+            Hsv::<u8>::from(Rgb::<u8>([0, 0, 255])),
+            Hsv([171, 255, 255])
+        );
+        assert_eq!(
+            Hsv180::<u8>::from(Rgb::<u8>([0, 0, 255])),
+            Hsv([120, 255, 255])
+        );
+        assert_eq!(
+            Hsv180::<u8>::from(Rgb::<u8>([255, 0, 1])),
+            Hsv([0, 255, 255])
+        );
+    }
+
+    #[test]
+    fn to_rgb() {
+        assert_eq!(
+            Rgb::<u8>::from(Hsv::<u8>([0, 0, 128])),
+            Rgb([128, 128, 128])
+        );
+        assert_eq!(
+            Rgb::<u8>::from(Hsv180::<u8>::from([60, 255, 255])),
+            Rgb([0, 255, 0])
+        );
+    }
+
+    #[test]
+    fn to_rgb_wrap() {
+        // hues outside of a single turn
+        for &(hue, rgb) in &[
+            (-120.0, [0.0, 0.0, 1.0]),
+            (-60.0, [1.0, 0.0, 1.0]),
+            (360.0, [1.0, 0.0, 0.0]),
+            (400.0, [1.0, 0.6666666, 0.0]),
+            (720.0 + 120.0, [0.0, 1.0, 0.0]),
+        ] {
+            let out = Rgb::<f32>::from(Hsv::<f32>([hue, 1.0, 1.0]));
+            for (out, rgb) in out.iter().zip(rgb.iter()) {
+                assert!(FloatCore::abs(out - rgb) < 1e-5, "{} {:?}", hue, out);
+            }
+        }
+    }
+}
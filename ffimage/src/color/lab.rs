@@ -0,0 +1,269 @@
+use core::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use num_traits::Float;
+
+use crate::{
+    color::{
+        rgb::Rgb,
+        transfer::TransferSample,
+        xyz::{WhitePoint, Xyz, D65},
+    },
+    Pixel,
+};
+
+/// CIE epsilon constant (216 / 24389)
+pub(crate) const EPSILON: f32 = 216.0 / 24389.0;
+
+/// CIE kappa constant (24389 / 27)
+pub(crate) const KAPPA: f32 = 24389.0 / 27.0;
+
+/// CIE 1976 L*a*b* pixel
+///
+/// Lightness covers the range from 0.0 to 100.0, a* and b* are unbounded but usually stay within
+/// -128.0 to 127.0. Values are relative to the white point `W`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Lab<T, W = D65>(pub [T; 3], PhantomData<W>);
+
+impl<T, W> From<[T; 3]> for Lab<T, W> {
+    fn from(value: [T; 3]) -> Self {
+        Lab(value, PhantomData)
+    }
+}
+
+impl<T, W> Deref for Lab<T, W> {
+    type Target = [T; 3];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, W> DerefMut for Lab<T, W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, W> Pixel for Lab<T, W> {
+    const CHANNELS: u8 = 3;
+}
+
+fn f(t: f32) -> f32 {
+    if t > EPSILON {
+        Float::cbrt(t)
+    } else {
+        (KAPPA * t + 16.0) / 116.0
+    }
+}
+
+fn f_inv(t: f32) -> f32 {
+    let t3 = t * t * t;
+    if t3 > EPSILON {
+        t3
+    } else {
+        (116.0 * t - 16.0) / KAPPA
+    }
+}
+
+impl<W> Lab<f32, W> {
+    /// Returns the CIE76 color difference, i.e. the euclidean distance of the two colors.
+    pub fn delta_e76(&self, other: &Self) -> f32 {
+        let dl = self[0] - other[0];
+        let da = self[1] - other[1];
+        let db = self[2] - other[2];
+        Float::sqrt(dl * dl + da * da + db * db)
+    }
+
+    /// Returns the CIEDE2000 color difference with unity weighting factors.
+    pub fn delta_e2000(&self, other: &Self) -> f32 {
+        let [l1, a1, b1] = self.0;
+        let [l2, a2, b2] = other.0;
+        let pow7 = |x: f32| Float::powi(x, 7);
+        let hue = |b: f32, a: f32| {
+            if a == 0.0 && b == 0.0 {
+                0.0
+            } else {
+                let h = Float::atan2(b, a).to_degrees();
+                if h < 0.0 {
+                    h + 360.0
+                } else {
+                    h
+                }
+            }
+        };
+
+        let c_mean = (Float::sqrt(a1 * a1 + b1 * b1) + Float::sqrt(a2 * a2 + b2 * b2)) / 2.0;
+        let g = 0.5 * (1.0 - Float::sqrt(pow7(c_mean) / (pow7(c_mean) + pow7(25.0))));
+        let a1 = a1 * (1.0 + g);
+        let a2 = a2 * (1.0 + g);
+        let c1 = Float::sqrt(a1 * a1 + b1 * b1);
+        let c2 = Float::sqrt(a2 * a2 + b2 * b2);
+        let h1 = hue(b1, a1);
+        let h2 = hue(b2, a2);
+
+        let dl = l2 - l1;
+        let dc = c2 - c1;
+        let dh = if c1 * c2 == 0.0 {
+            0.0
+        } else if h2 - h1 > 180.0 {
+            h2 - h1 - 360.0
+        } else if h2 - h1 < -180.0 {
+            h2 - h1 + 360.0
+        } else {
+            h2 - h1
+        };
+        let dh = 2.0 * Float::sqrt(c1 * c2) * Float::sin((dh / 2.0).to_radians());
+
+        let l_mean = (l1 + l2) / 2.0;
+        let c_mean = (c1 + c2) / 2.0;
+        let h_mean = if c1 * c2 == 0.0 {
+            h1 + h2
+        } else if Float::abs(h1 - h2) <= 180.0 {
+            (h1 + h2) / 2.0
+        } else if h1 + h2 < 360.0 {
+            (h1 + h2 + 360.0) / 2.0
+        } else {
+            (h1 + h2 - 360.0) / 2.0
+        };
+
+        let cos = |deg: f32| Float::cos(deg.to_radians());
+        let t = 1.0 - 0.17 * cos(h_mean - 30.0)
+            + 0.24 * cos(2.0 * h_mean)
+            + 0.32 * cos(3.0 * h_mean + 6.0)
+            - 0.20 * cos(4.0 * h_mean - 63.0);
+        let d_theta = 30.0 * Float::exp(-Float::powi((h_mean - 275.0) / 25.0, 2));
+        let r_c = 2.0 * Float::sqrt(pow7(c_mean) / (pow7(c_mean) + pow7(25.0)));
+        let l_50 = (l_mean - 50.0) * (l_mean - 50.0);
+        let s_l = 1.0 + 0.015 * l_50 / Float::sqrt(20.0 + l_50);
+        let s_c = 1.0 + 0.045 * c_mean;
+        let s_h = 1.0 + 0.015 * c_mean * t;
+        let r_t = -Float::sin((2.0 * d_theta).to_radians()) * r_c;
+
+        let dl = dl / s_l;
+        let dc = dc / s_c;
+        let dh = dh / s_h;
+        Float::sqrt(dl * dl + dc * dc + dh * dh + r_t * dc * dh)
+    }
+}
+
+impl<W> From<Xyz<f32, W>> for Lab<f32, W>
+where
+    W: WhitePoint,
+{
+    fn from(xyz: Xyz<f32, W>) -> Self {
+        let fx = f(xyz[0] / W::XYZ[0]);
+        let fy = f(xyz[1] / W::XYZ[1]);
+        let fz = f(xyz[2] / W::XYZ[2]);
+
+        Lab::from([116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)])
+    }
+}
+
+impl<W> From<Lab<f32, W>> for Xyz<f32, W>
+where
+    W: WhitePoint,
+{
+    fn from(lab: Lab<f32, W>) -> Self {
+        let fy = (lab[0] + 16.0) / 116.0;
+        let fx = fy + lab[1] / 500.0;
+        let fz = fy - lab[2] / 200.0;
+        let y = if lab[0] > KAPPA * EPSILON {
+            fy * fy * fy
+        } else {
+            lab[0] / KAPPA
+        };
+
+        Xyz::from([f_inv(fx) * W::XYZ[0], y * W::XYZ[1], f_inv(fz) * W::XYZ[2]])
+    }
+}
+
+impl<T, W, const R: usize, const G: usize, const B: usize> From<Rgb<T, R, G, B>> for Lab<f32, W>
+where
+    T: TransferSample,
+    W: WhitePoint,
+{
+    fn from(rgb: Rgb<T, R, G, B>) -> Self {
+        Lab::from(Xyz::<f32, W>::from(rgb))
+    }
+}
+
+impl<T, W, const R: usize, const G: usize, const B: usize> From<Lab<f32, W>> for Rgb<T, R, G, B>
+where
+    T: TransferSample + Default,
+    W: WhitePoint,
+{
+    fn from(lab: Lab<f32, W>) -> Self {
+        Rgb::from(Xyz::<f32, W>::from(lab))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::D50;
+
+    fn assert_near(a: [f32; 3], b: [f32; 3], delta: f32) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < delta, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn channels() {
+        assert_eq!(Lab::<f32>::CHANNELS, 3);
+    }
+
+    #[test]
+    fn from_rgb() {
+        let white = Lab::<f32>::from(Rgb::<u8>([255, 255, 255]));
+        assert_near(*white, [100.0, 0.0, 0.0], 1e-2);
+
+        let red = Lab::<f32>::from(Rgb::<u8>([255, 0, 0]));
+        assert_near(*red, [53.24, 80.09, 67.20], 1e-2);
+
+        let red = Lab::<f32, D50>::from(Rgb::<u8>([255, 0, 0]));
+        assert_near(*red, [54.29, 80.80, 69.89], 5e-2);
+    }
+
+    #[test]
+    fn to_rgb() {
+        for rgb in [[255, 0, 0], [0, 128, 0], [1, 2, 3], [250, 250, 250]] {
+            let rgb = Rgb::<u8>(rgb);
+            assert_eq!(Rgb::<u8>::from(Lab::<f32>::from(rgb)), rgb);
+            assert_eq!(Rgb::<u8>::from(Lab::<f32, D50>::from(rgb)), rgb);
+        }
+    }
+
+    #[test]
+    fn delta_e76() {
+        let a = Lab::<f32>::from([50.0, 10.0, 10.0]);
+        let b = Lab::<f32>::from([53.0, 6.0, 10.0]);
+
+        assert_eq!(a.delta_e76(&b), 5.0);
+        assert_eq!(a.delta_e76(&a), 0.0);
+    }
+
+    #[test]
+    fn delta_e2000() {
+        // test data by Sharma, Wu and Dalal
+        let pairs = [
+            ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+            ([50.0, 3.1571, -77.2803], [50.0, 0.0, -82.7485], 2.8615),
+            ([50.0, 0.0, 0.0], [50.0, -1.0, 2.0], 2.3669),
+            ([50.0, 2.49, -0.001], [50.0, -2.49, 0.0009], 7.1792),
+            ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+            ([2.0776, 0.0795, -1.135], [0.9033, -0.0636, -0.5514], 0.9082),
+        ];
+
+        for (a, b, expected) in pairs {
+            let a = Lab::<f32>::from(a);
+            let b = Lab::<f32>::from(b);
+            assert!((a.delta_e2000(&b) - expected).abs() < 1e-3);
+            assert!((b.delta_e2000(&a) - expected).abs() < 1e-3);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use core::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    color::{
+        rgb::Rgb,
+        transfer::{Srgb, TransferFunction, TransferSample},
+        xyz::{WhitePoint, Xyz},
+    },
+    Pixel,
+};
+
+/// Linear light RGB pixel
+///
+/// In contrast to [`Rgb`], the samples are proportional to the light intensity, which is what
+/// averaging (e.g. for downscaling) and blending operations need to produce correct results.
+/// Converting from and to `Rgb` applies the transfer function `F`, which defaults to sRGB:
+///
+/// ```
+/// use ffimage::color::{LinearRgb, Rgb};
+///
+/// let black = LinearRgb::<f32>::from(Rgb::<u8>([0, 0, 0]));
+/// let white = LinearRgb::<f32>::from(Rgb::<u8>([255, 255, 255]));
+/// let gray = LinearRgb::<f32>::from([
+///     (black[0] + white[0]) / 2.0,
+///     (black[1] + white[1]) / 2.0,
+///     (black[2] + white[2]) / 2.0,
+/// ]);
+/// assert_eq!(Rgb::<u8>::from(gray), Rgb([188, 188, 188]));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct LinearRgb<T, F = Srgb>(pub [T; 3], PhantomData<F>);
+
+impl<T, F> From<[T; 3]> for LinearRgb<T, F> {
+    fn from(value: [T; 3]) -> Self {
+        LinearRgb(value, PhantomData)
+    }
+}
+
+impl<T, F> Deref for LinearRgb<T, F> {
+    type Target = [T; 3];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, F> DerefMut for LinearRgb<T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, F> Pixel for LinearRgb<T, F> {
+    const CHANNELS: u8 = 3;
+}
+
+impl<T, F, const R: usize, const G: usize, const B: usize> From<Rgb<T, R, G, B>>
+    for LinearRgb<f32, F>
+where
+    T: TransferSample,
+    F: TransferFunction,
+{
+    fn from(rgb: Rgb<T, R, G, B>) -> Self {
+        LinearRgb::from([
+            rgb[R].decode::<F>(),
+            rgb[G].decode::<F>(),
+            rgb[B].decode::<F>(),
+        ])
+    }
+}
+
+impl<T, F, const R: usize, const G: usize, const B: usize> From<LinearRgb<f32, F>>
+    for Rgb<T, R, G, B>
+where
+    T: TransferSample + Default,
+    F: TransferFunction,
+{
+    fn from(linear: LinearRgb<f32, F>) -> Self {
+        let mut rgb = Rgb::<T, R, G, B>::default();
+        rgb[R] = T::encode::<F>(linear[0]);
+        rgb[G] = T::encode::<F>(linear[1]);
+        rgb[B] = T::encode::<F>(linear[2]);
+        rgb
+    }
+}
+
+/// The samples are linear light already, so the transfer function `F` does not matter.
+impl<F, W> From<LinearRgb<f32, F>> for Xyz<f32, W>
+where
+    W: WhitePoint,
+{
+    fn from(linear: LinearRgb<f32, F>) -> Self {
+        Xyz::from_linear(Rgb::<f32>(linear.0))
+    }
+}
+
+impl<F, W> From<Xyz<f32, W>> for LinearRgb<f32, F>
+where
+    W: WhitePoint,
+{
+    fn from(xyz: Xyz<f32, W>) -> Self {
+        LinearRgb::from(*xyz.to_linear::<0, 1, 2>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{Bgr, Bt1886, Bt709Oetf};
+
+    #[test]
+    fn channels() {
+        assert_eq!(LinearRgb::<f32>::CHANNELS, 3);
+    }
+
+    #[test]
+    fn from_rgb() {
+        let bgr = Bgr::<u8>::from([0, 128, 255]);
+
+        assert_eq!(
+            LinearRgb::<f32>::from(bgr),
+            LinearRgb::from([1.0, Srgb::decode_u8(128), 0.0])
+        );
+        assert_eq!(
+            LinearRgb::<f32, Bt1886>::from(Rgb::<f32>([0.5, 0.0, 1.0])),
+            LinearRgb::from([Bt1886::decode(0.5), 0.0, 1.0])
+        );
+    }
+
+    #[test]
+    fn to_rgb() {
+        for v in 0..=255u8 {
+            let rgb = Rgb::<u8>([v, v / 2, 255 - v]);
+            assert_eq!(Rgb::<u8>::from(LinearRgb::<f32>::from(rgb)), rgb);
+            assert_eq!(Rgb::<u8>::from(LinearRgb::<f32, Bt709Oetf>::from(rgb)), rgb);
+        }
+
+        assert_eq!(
+            Bgr::<u16>::from(LinearRgb::<f32>::from([1.0, 0.0, 0.0])),
+            Bgr::from([0, 0, u16::MAX])
+        );
+    }
+
+    #[test]
+    fn xyz() {
+        let rgb = Rgb::<u8>([10, 100, 200]);
+        let xyz = Xyz::<f32>::from(LinearRgb::<f32>::from(rgb));
+
+        assert_eq!(xyz, Xyz::from(rgb));
+        assert_eq!(Rgb::<u8>::from(LinearRgb::<f32>::from(xyz)), rgb);
+
+        let linear = LinearRgb::<f32, Bt1886>::from(rgb);
+        let xyz = Xyz::<f32>::from(linear);
+        assert_eq!(xyz, Xyz::from_linear(Rgb::<f32>(*linear)));
+        assert_eq!(Rgb::<u8>::from(LinearRgb::<f32, Bt1886>::from(xyz)), rgb);
+    }
+}
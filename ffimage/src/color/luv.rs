@@ -0,0 +1,166 @@
+use core::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use num_traits::Float;
+
+use crate::{
+    color::{
+        lab::{EPSILON, KAPPA},
+        rgb::Rgb,
+        transfer::TransferSample,
+        xyz::{WhitePoint, Xyz, D65},
+    },
+    Pixel,
+};
+
+/// CIE 1976 L*u*v* pixel
+///
+/// Lightness covers the range from 0.0 to 100.0, u* and v* are unbounded. Values are relative to
+/// the white point `W`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Luv<T, W = D65>(pub [T; 3], PhantomData<W>);
+
+impl<T, W> From<[T; 3]> for Luv<T, W> {
+    fn from(value: [T; 3]) -> Self {
+        Luv(value, PhantomData)
+    }
+}
+
+impl<T, W> Deref for Luv<T, W> {
+    type Target = [T; 3];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, W> DerefMut for Luv<T, W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, W> Pixel for Luv<T, W> {
+    const CHANNELS: u8 = 3;
+}
+
+/// Returns the u' and v' chromaticity coordinates of XYZ values.
+fn uv(xyz: [f32; 3]) -> (f32, f32) {
+    let denom = xyz[0] + 15.0 * xyz[1] + 3.0 * xyz[2];
+    if denom == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    (4.0 * xyz[0] / denom, 9.0 * xyz[1] / denom)
+}
+
+impl<W> From<Xyz<f32, W>> for Luv<f32, W>
+where
+    W: WhitePoint,
+{
+    fn from(xyz: Xyz<f32, W>) -> Self {
+        let (u, v) = uv(xyz.0);
+        let (un, vn) = uv(W::XYZ);
+        let y = xyz[1] / W::XYZ[1];
+        let l = if y > EPSILON {
+            116.0 * Float::cbrt(y) - 16.0
+        } else {
+            KAPPA * y
+        };
+
+        if l == 0.0 {
+            return Luv::from([0.0, 0.0, 0.0]);
+        }
+
+        Luv::from([l, 13.0 * l * (u - un), 13.0 * l * (v - vn)])
+    }
+}
+
+impl<W> From<Luv<f32, W>> for Xyz<f32, W>
+where
+    W: WhitePoint,
+{
+    fn from(luv: Luv<f32, W>) -> Self {
+        let l = luv[0];
+        if l <= 0.0 {
+            return Xyz::from([0.0, 0.0, 0.0]);
+        }
+
+        let (un, vn) = uv(W::XYZ);
+        let u = luv[1] / (13.0 * l) + un;
+        let v = luv[2] / (13.0 * l) + vn;
+        let y = if l > KAPPA * EPSILON {
+            let fy = (l + 16.0) / 116.0;
+            fy * fy * fy
+        } else {
+            l / KAPPA
+        } * W::XYZ[1];
+
+        Xyz::from([
+            y * 9.0 * u / (4.0 * v),
+            y,
+            y * (12.0 - 3.0 * u - 20.0 * v) / (4.0 * v),
+        ])
+    }
+}
+
+impl<T, W, const R: usize, const G: usize, const B: usize> From<Rgb<T, R, G, B>> for Luv<f32, W>
+where
+    T: TransferSample,
+    W: WhitePoint,
+{
+    fn from(rgb: Rgb<T, R, G, B>) -> Self {
+        Luv::from(Xyz::<f32, W>::from(rgb))
+    }
+}
+
+impl<T, W, const R: usize, const G: usize, const B: usize> From<Luv<f32, W>> for Rgb<T, R, G, B>
+where
+    T: TransferSample + Default,
+    W: WhitePoint,
+{
+    fn from(luv: Luv<f32, W>) -> Self {
+        Rgb::from(Xyz::<f32, W>::from(luv))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::D50;
+
+    fn assert_near(a: [f32; 3], b: [f32; 3], delta: f32) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < delta, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn channels() {
+        assert_eq!(Luv::<f32>::CHANNELS, 3);
+    }
+
+    #[test]
+    fn from_rgb() {
+        let white = Luv::<f32>::from(Rgb::<u8>([255, 255, 255]));
+        assert_near(*white, [100.0, 0.0, 0.0], 1e-2);
+
+        let red = Luv::<f32>::from(Rgb::<u8>([255, 0, 0]));
+        assert_near(*red, [53.24, 175.01, 37.76], 5e-2);
+
+        let black = Luv::<f32>::from(Rgb::<u8>([0, 0, 0]));
+        assert_eq!(black, Luv::from([0.0, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn to_rgb() {
+        for rgb in [[255, 0, 0], [0, 128, 0], [0, 0, 0], [250, 250, 250]] {
+            let rgb = Rgb::<u8>(rgb);
+            assert_eq!(Rgb::<u8>::from(Luv::<f32>::from(rgb)), rgb);
+            assert_eq!(Rgb::<u8>::from(Luv::<f32, D50>::from(rgb)), rgb);
+        }
+    }
+}
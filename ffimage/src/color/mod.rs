@@ -1,5 +1,38 @@
+pub mod alpha;
+pub use alpha::*;
+
 pub mod gray;
 pub use gray::*;
 
+pub mod gray_alpha;
+pub use gray_alpha::*;
+
+pub mod hsl;
+pub use hsl::*;
+
+pub mod hsv;
+pub use hsv::*;
+
+pub mod lab;
+pub use lab::*;
+
+pub mod linear_rgb;
+pub use linear_rgb::*;
+
+pub mod luv;
+pub use luv::*;
+
+pub mod packed;
+pub use packed::*;
+
 pub mod rgb;
 pub use rgb::*;
+
+pub mod rgba;
+pub use rgba::*;
+
+pub mod transfer;
+pub use transfer::*;
+
+pub mod xyz;
+pub use xyz::*;
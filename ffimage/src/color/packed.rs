@@ -0,0 +1,501 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::{
+    color::{rgb::Rgb, rgba::Rgba},
+    sample::{Bits, Sample},
+    Pixel,
+};
+
+/// Returns the 8-bit value of a channel by replicating its most significant bits.
+fn unpack(value: u16, shift: u32, bits: u32) -> u8 {
+    let v = (value >> shift) & ((1 << bits) - 1);
+    ((v << (8 - bits)) | (v >> (2 * bits - 8))) as u8
+}
+
+/// Returns an 8-bit value rounded to a channel of the given bit depth, shifted into place.
+fn pack(value: u8, shift: u32, bits: u32) -> u16 {
+    let max = (1 << bits) - 1;
+    (((value as u32 * max + 127) / 255) << shift) as u16
+}
+
+macro_rules! packed16 {
+    ($(#[$meta:meta])* $name:ident, $be:ident, $fmt:literal) => {
+        $(#[$meta])*
+        ///
+        /// The pixel is stored as two bytes in little-endian order by default, set `BE` for
+        #[doc = concat!("big-endian order (see [`", stringify!($be), "`]).")]
+        /// The samples of the pixel are these bytes, so pixels are read from and written to byte
+        /// streams. Use the `u16` conversions to access the packed word.
+        #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+        #[repr(transparent)]
+        pub struct $name<const BE: bool = false>(pub [u8; 2]);
+
+        #[doc = concat!("Big-endian ", $fmt, " pixel")]
+        pub type $be = $name<true>;
+
+        // SAFETY: transparent wrapper of [u8; 2]
+        unsafe impl<const BE: bool> crate::cast::Transparent for $name<BE> {
+            type Sample = u8;
+        }
+
+        impl<const BE: bool> From<u16> for $name<BE> {
+            fn from(value: u16) -> Self {
+                if BE {
+                    $name(value.to_be_bytes())
+                } else {
+                    $name(value.to_le_bytes())
+                }
+            }
+        }
+
+        impl<const BE: bool> From<$name<BE>> for u16 {
+            fn from(pix: $name<BE>) -> Self {
+                if BE {
+                    u16::from_be_bytes(pix.0)
+                } else {
+                    u16::from_le_bytes(pix.0)
+                }
+            }
+        }
+
+        impl<const BE: bool> From<[u8; 2]> for $name<BE> {
+            fn from(value: [u8; 2]) -> Self {
+                $name(value)
+            }
+        }
+
+        impl<const BE: bool> Deref for $name<BE> {
+            type Target = [u8; 2];
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<const BE: bool> DerefMut for $name<BE> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl<const BE: bool> Pixel for $name<BE> {
+            const CHANNELS: u8 = 2;
+        }
+    };
+}
+
+packed16!(
+    /// 16-bit packed RGB pixel with 5 bits red, 6 bits green and 5 bits blue (from MSB to LSB)
+    Rgb565,
+    Rgb565Be,
+    "RGB565"
+);
+
+packed16!(
+    /// 16-bit packed BGR pixel with 5 bits blue, 6 bits green and 5 bits red (from MSB to LSB)
+    Bgr565,
+    Bgr565Be,
+    "BGR565"
+);
+
+packed16!(
+    /// 16-bit packed RGB pixel with 1 unused bit and 5 bits per channel (from MSB to LSB)
+    Rgb555,
+    Rgb555Be,
+    "RGB555"
+);
+
+packed16!(
+    /// 16-bit packed RGB pixel with 1 bit alpha and 5 bits per channel (from MSB to LSB)
+    Argb1555,
+    Argb1555Be,
+    "ARGB1555"
+);
+
+macro_rules! impl_packed16_rgb {
+    (
+        $name:ident,
+        [$r:literal, $r_bits:literal],
+        [$g:literal, $g_bits:literal],
+        [$b:literal, $b_bits:literal],
+        $opaque:literal
+    ) => {
+        impl<const BE: bool, const R: usize, const G: usize, const B: usize> From<$name<BE>>
+            for Rgb<u8, R, G, B>
+        {
+            fn from(pix: $name<BE>) -> Self {
+                let value = u16::from(pix);
+
+                let mut rgb = Rgb::<u8, R, G, B>::default();
+                rgb[R] = unpack(value, $r, $r_bits);
+                rgb[G] = unpack(value, $g, $g_bits);
+                rgb[B] = unpack(value, $b, $b_bits);
+                rgb
+            }
+        }
+
+        impl<const BE: bool, const R: usize, const G: usize, const B: usize> From<Rgb<u8, R, G, B>>
+            for $name<BE>
+        {
+            fn from(rgb: Rgb<u8, R, G, B>) -> Self {
+                $name::from(
+                    $opaque
+                        | pack(rgb[R], $r, $r_bits)
+                        | pack(rgb[G], $g, $g_bits)
+                        | pack(rgb[B], $b, $b_bits),
+                )
+            }
+        }
+    };
+}
+
+// the last argument holds the bits which are set for opaque pixels, i.e. the alpha bit
+impl_packed16_rgb!(Rgb565, [11, 5], [5, 6], [0, 5], 0x0000);
+impl_packed16_rgb!(Bgr565, [0, 5], [5, 6], [11, 5], 0x0000);
+impl_packed16_rgb!(Rgb555, [10, 5], [5, 5], [0, 5], 0x0000);
+impl_packed16_rgb!(Argb1555, [10, 5], [5, 5], [0, 5], 0x8000);
+
+impl<const BE: bool, const R: usize, const G: usize, const B: usize, const A: usize>
+    From<Argb1555<BE>> for Rgba<u8, R, G, B, A>
+{
+    fn from(pix: Argb1555<BE>) -> Self {
+        let value = u16::from(pix);
+
+        let mut rgba = Rgba::<u8, R, G, B, A>::default();
+        rgba[R] = unpack(value, 10, 5);
+        rgba[G] = unpack(value, 5, 5);
+        rgba[B] = unpack(value, 0, 5);
+        rgba[A] = if value & 0x8000 != 0 { u8::MAX } else { 0 };
+        rgba
+    }
+}
+
+impl<const BE: bool, const R: usize, const G: usize, const B: usize, const A: usize>
+    From<Rgba<u8, R, G, B, A>> for Argb1555<BE>
+{
+    fn from(rgba: Rgba<u8, R, G, B, A>) -> Self {
+        // alpha values of one half and above are considered opaque
+        let alpha = if rgba[A] >= 128 { 0x8000 } else { 0 };
+        Argb1555::from(alpha | pack(rgba[R], 10, 5) | pack(rgba[G], 5, 5) | pack(rgba[B], 0, 5))
+    }
+}
+
+/// Returns a channel of the given bit depth as sample.
+fn unpack_sample<T: Sample, const N: u32>(value: u32, shift: u32) -> T {
+    Bits::<N>(((value >> shift) & ((1 << N) - 1)) as u16).to_sample()
+}
+
+/// Returns a sample rounded to a channel of the given bit depth, shifted into place.
+fn pack_sample<T: Sample, const N: u32>(value: T, shift: u32) -> u32 {
+    (value.to_sample::<Bits<N>>().0 as u32) << shift
+}
+
+macro_rules! packed32 {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        ///
+        /// The pixel is stored as 32-bit word in little-endian byte order. The samples of the pixel
+        /// are these bytes, so pixels are read from and written to byte streams. Use the `u32`
+        /// conversions to access the packed word.
+        #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+        #[repr(transparent)]
+        pub struct $name(pub [u8; 4]);
+
+        // SAFETY: transparent wrapper of [u8; 4]
+        unsafe impl crate::cast::Transparent for $name {
+            type Sample = u8;
+        }
+
+        impl From<u32> for $name {
+            fn from(value: u32) -> Self {
+                $name(value.to_le_bytes())
+            }
+        }
+
+        impl From<$name> for u32 {
+            fn from(pix: $name) -> Self {
+                u32::from_le_bytes(pix.0)
+            }
+        }
+
+        impl From<[u8; 4]> for $name {
+            fn from(value: [u8; 4]) -> Self {
+                $name(value)
+            }
+        }
+
+        impl Deref for $name {
+            type Target = [u8; 4];
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl DerefMut for $name {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl Pixel for $name {
+            const CHANNELS: u8 = 4;
+        }
+    };
+}
+
+packed32!(
+    /// 32-bit packed RGB pixel with 2 bits alpha and 10 bits blue, green and red (from MSB to
+    /// LSB), also known as `GL_RGB10_A2` or `DRM_FORMAT_ABGR2101010`
+    Rgb10a2
+);
+
+packed32!(
+    /// 32-bit packed BGR pixel with 2 bits alpha and 10 bits red, green and blue (from MSB to
+    /// LSB), also known as `DRM_FORMAT_ARGB2101010`
+    Bgr10a2
+);
+
+packed32!(
+    /// 32-bit packed RGB pixel with 2 unused bits and 10 bits red, green and blue (from MSB to
+    /// LSB), also known as `DRM_FORMAT_XRGB2101010`
+    X2Rgb10
+);
+
+packed32!(
+    /// 32-bit packed BGR pixel with 2 unused bits and 10 bits blue, green and red (from MSB to
+    /// LSB), also known as `DRM_FORMAT_XBGR2101010`
+    X2Bgr10
+);
+
+macro_rules! impl_packed32_rgb {
+    ($name:ident, $r:literal, $g:literal, $b:literal, $opaque:literal) => {
+        impl<T, const R: usize, const G: usize, const B: usize> From<$name> for Rgb<T, R, G, B>
+        where
+            T: Sample + Default,
+        {
+            fn from(pix: $name) -> Self {
+                let value = u32::from(pix);
+
+                let mut rgb = Rgb::<T, R, G, B>::default();
+                rgb[R] = unpack_sample::<T, 10>(value, $r);
+                rgb[G] = unpack_sample::<T, 10>(value, $g);
+                rgb[B] = unpack_sample::<T, 10>(value, $b);
+                rgb
+            }
+        }
+
+        impl<T, const R: usize, const G: usize, const B: usize> From<Rgb<T, R, G, B>> for $name
+        where
+            T: Sample,
+        {
+            fn from(rgb: Rgb<T, R, G, B>) -> Self {
+                $name::from(
+                    $opaque
+                        | pack_sample::<T, 10>(rgb[R], $r)
+                        | pack_sample::<T, 10>(rgb[G], $g)
+                        | pack_sample::<T, 10>(rgb[B], $b),
+                )
+            }
+        }
+    };
+}
+
+// the last argument holds the bits which are set for opaque pixels, i.e. the alpha bits
+impl_packed32_rgb!(Rgb10a2, 0, 10, 20, 0xc000_0000);
+impl_packed32_rgb!(Bgr10a2, 20, 10, 0, 0xc000_0000);
+impl_packed32_rgb!(X2Rgb10, 20, 10, 0, 0x0000_0000);
+impl_packed32_rgb!(X2Bgr10, 0, 10, 20, 0x0000_0000);
+
+macro_rules! impl_packed32_rgba {
+    ($name:ident, $r:literal, $g:literal, $b:literal) => {
+        impl<T, const R: usize, const G: usize, const B: usize, const A: usize> From<$name>
+            for Rgba<T, R, G, B, A>
+        where
+            T: Sample + Default,
+        {
+            fn from(pix: $name) -> Self {
+                let value = u32::from(pix);
+
+                let mut rgba = Rgba::<T, R, G, B, A>::default();
+                rgba[R] = unpack_sample::<T, 10>(value, $r);
+                rgba[G] = unpack_sample::<T, 10>(value, $g);
+                rgba[B] = unpack_sample::<T, 10>(value, $b);
+                rgba[A] = unpack_sample::<T, 2>(value, 30);
+                rgba
+            }
+        }
+
+        impl<T, const R: usize, const G: usize, const B: usize, const A: usize>
+            From<Rgba<T, R, G, B, A>> for $name
+        where
+            T: Sample,
+        {
+            fn from(rgba: Rgba<T, R, G, B, A>) -> Self {
+                $name::from(
+                    pack_sample::<T, 2>(rgba[A], 30)
+                        | pack_sample::<T, 10>(rgba[R], $r)
+                        | pack_sample::<T, 10>(rgba[G], $g)
+                        | pack_sample::<T, 10>(rgba[B], $b),
+                )
+            }
+        }
+    };
+}
+
+impl_packed32_rgba!(Rgb10a2, 0, 10, 20);
+impl_packed32_rgba!(Bgr10a2, 20, 10, 0);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::Bgr,
+        iter::{BytesExt, ColorConvertExt, PixelsExt},
+    };
+
+    #[test]
+    fn channels() {
+        assert_eq!(Rgb565::<false>::CHANNELS, 2);
+        assert_eq!(Argb1555Be::CHANNELS, 2);
+        assert_eq!(X2Rgb10::CHANNELS, 4);
+    }
+
+    #[test]
+    fn image_view() {
+        use crate::image::ImageView;
+
+        // 2x1 RGB565 image with two bytes of padding
+        let buf = [0x00, 0xf8, 0xe0, 0x07, 0, 0];
+        let view = ImageView::<Rgb565>::with_stride(&buf, 2, 1, 6).unwrap();
+        assert_eq!(view.row(0), Some(&buf[..4]));
+
+        let mut pixels = view.samples().pixels::<Rgb565>();
+        assert_eq!(pixels.next().map(u16::from), Some(0xf800));
+        assert_eq!(pixels.next().map(u16::from), Some(0x07e0));
+        assert_eq!(pixels.next(), None);
+    }
+
+    #[test]
+    fn endianness() {
+        assert_eq!(Rgb565::<false>::from(0xf800), Rgb565([0x00, 0xf8]));
+        assert_eq!(Rgb565Be::from(0xf800), Rgb565([0xf8, 0x00]));
+        assert_eq!(u16::from(Rgb565Be::from([0x12, 0x34])), 0x1234);
+    }
+
+    #[test]
+    fn bit_replication() {
+        for v in 0..32u16 {
+            let rgb = Rgb::<u8>::from(Rgb555::<false>::from(v << 10 | v << 5 | v));
+            assert_eq!(rgb, Rgb([(v << 3 | v >> 2) as u8; 3]));
+            assert_eq!(u16::from(Rgb555::<false>::from(rgb)), v << 10 | v << 5 | v);
+        }
+        for v in 0..64u16 {
+            let rgb = Rgb::<u8>::from(Rgb565::<false>::from(v << 5));
+            assert_eq!(u16::from(Rgb565::<false>::from(rgb)), v << 5);
+        }
+
+        assert_eq!(
+            Rgb::<u8>::from(Rgb565::<false>::from(0xffff)),
+            Rgb([255; 3])
+        );
+        assert_eq!(
+            Rgb::<u8>::from(Bgr565::<false>::from(0x001f)),
+            Rgb([255, 0, 0])
+        );
+    }
+
+    #[test]
+    fn alpha() {
+        let rgba = Rgba::<u8>::from(Argb1555::<false>::from(0xfc00));
+        assert_eq!(rgba, Rgba([255, 0, 0, 255]));
+        assert_eq!(u16::from(Argb1555::<false>::from(rgba)), 0xfc00);
+
+        let rgba = Rgba::<u8>([0, 0, 255, 127]);
+        assert_eq!(u16::from(Argb1555::<false>::from(rgba)), 0x001f);
+        assert_eq!(
+            u16::from(Argb1555::<false>::from(Rgb::<u8>([0, 0, 0]))),
+            0x8000
+        );
+    }
+
+    #[test]
+    fn iter() {
+        let buf = [0x00, 0xf8, 0xe0, 0x07];
+        let mut out = [0; 6];
+        buf.iter()
+            .copied()
+            .pixels::<Rgb565>()
+            .colorconvert::<Bgr<u8>>()
+            .bytes()
+            .write(&mut out);
+        assert_eq!(out, [0, 0, 255, 0, 255, 0]);
+
+        let buf = [0, 0, 255, 0, 255, 0];
+        let mut out = [0; 4];
+        buf.iter()
+            .copied()
+            .pixels::<Bgr<u8>>()
+            .colorconvert::<Rgb565Be>()
+            .bytes()
+            .write(&mut out);
+        assert_eq!(out, [0xf8, 0x00, 0x07, 0xe0]);
+
+        let mut pixels = out.iter().copied().pixels::<Rgb565Be>();
+        assert_eq!(u16::from(pixels.next().unwrap()), 0xf800);
+        assert_eq!(u16::from(pixels.next().unwrap()), 0x07e0);
+        assert_eq!(pixels.next(), None);
+    }
+
+    #[test]
+    fn rgb10() {
+        let pix = X2Rgb10::from(0x3ff0_0000);
+        assert_eq!(Rgb::<u16>::from(pix), Rgb([u16::MAX, 0, 0]));
+        assert_eq!(Rgb::<f32>::from(pix), Rgb([1.0, 0.0, 0.0]));
+        assert_eq!(
+            Rgb::<u16>::from(X2Bgr10::from(0x3ff)),
+            Rgb([u16::MAX, 0, 0])
+        );
+
+        let rgb = Rgb::<f32>([1.0, 512.0 / 1023.0, 0.0]);
+        assert_eq!(u32::from(X2Rgb10::from(rgb)), 0x3ff8_0000);
+        assert_eq!(u32::from(Rgb10a2::from(rgb)), 0xc008_03ff);
+        assert_eq!(u32::from(Bgr10a2::from(rgb)), 0xfff8_0000);
+
+        for v in 0..1024u32 {
+            let pix = X2Rgb10::from(v << 20 | v << 10 | v);
+            assert_eq!(X2Rgb10::from(Rgb::<u16>::from(pix)), pix);
+            assert_eq!(X2Rgb10::from(Rgb::<f32>::from(pix)), pix);
+        }
+    }
+
+    #[test]
+    fn rgb10_alpha() {
+        let pix = Rgb10a2::from(0x4000_03ff);
+        let rgba = Rgba::<u16>::from(pix);
+        assert_eq!(rgba, Rgba([u16::MAX, 0, 0, 0x5555]));
+        assert_eq!(Rgb10a2::from(rgba), pix);
+        assert_eq!(
+            Bgr10a2::from(Rgba::<f32>([0.0, 0.0, 1.0, 1.0])),
+            Bgr10a2::from(0xc000_03ff)
+        );
+    }
+
+    #[test]
+    fn iter_rgb10() {
+        let buf = [0xff, 0x03, 0x00, 0xc0, 0x00, 0x00, 0xf0, 0x3f];
+        let mut out = [0; 6];
+        buf.iter()
+            .copied()
+            .pixels::<Rgb10a2>()
+            .colorconvert::<Rgb<u16>>()
+            .bytes()
+            .write(&mut out);
+        assert_eq!(out, [u16::MAX, 0, 0, 0, 0, u16::MAX]);
+
+        let mut pixels = buf.iter().copied().pixels::<X2Rgb10>();
+        assert_eq!(pixels.next(), Some(X2Rgb10::from(0xc000_03ff)));
+        assert_eq!(pixels.next(), Some(X2Rgb10::from(0x3ff0_0000)));
+        assert_eq!(pixels.next(), None);
+    }
+}
@@ -1,9 +1,13 @@
 use core::ops::{Deref, DerefMut};
 
-use crate::Pixel;
+use crate::{
+    sample::{FromDepth, Sample},
+    Pixel,
+};
 
 /// RGB pixel
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
 pub struct Rgb<T, const R: usize = 0, const G: usize = 1, const B: usize = 2>(pub [T; 3]);
 
 /// BGR pixel
@@ -53,6 +57,17 @@ where
     }
 }
 
+impl<T, U, const R: usize, const G: usize, const B: usize> FromDepth<Rgb<U, R, G, B>>
+    for Rgb<T, R, G, B>
+where
+    T: Sample,
+    U: Sample,
+{
+    fn from_depth(rgb: Rgb<U, R, G, B>) -> Self {
+        Rgb([rgb[0].to_sample(), rgb[1].to_sample(), rgb[2].to_sample()])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,6 +78,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::init_numbered_fields)]
     fn index_mut() {
         let pix: Rgb<u8> = Rgb { 0: [1, 2, 3] };
 
@@ -70,4 +86,15 @@ mod tests {
         assert_eq!(pix.0[1], 2);
         assert_eq!(pix.0[2], 3);
     }
+
+    #[test]
+    fn from_depth() {
+        let rgb = Rgb::<u16>([0, 0x8080, 0xffff]);
+
+        assert_eq!(Rgb::<u8>::from_depth(rgb), Rgb([0, 128, 255]));
+        assert_eq!(
+            Rgb::<f32>::from_depth(rgb),
+            Rgb([0.0, 0x8080 as f32 / 65535.0, 1.0])
+        );
+    }
 }
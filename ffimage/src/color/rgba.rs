@@ -0,0 +1,238 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::{
+    color::{
+        gray::{Gray, LumaSample},
+        rgb::Rgb,
+    },
+    sample::{FromDepth, Sample},
+    Pixel,
+};
+
+/// RGB pixel with alpha channel
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Rgba<T, const R: usize = 0, const G: usize = 1, const B: usize = 2, const A: usize = 3>(
+    pub [T; 4],
+);
+
+/// BGRA pixel
+pub type Bgra<T> = Rgba<T, 2, 1, 0, 3>;
+
+/// ARGB pixel
+pub type Argb<T> = Rgba<T, 1, 2, 3, 0>;
+
+/// ABGR pixel
+pub type Abgr<T> = Rgba<T, 3, 2, 1, 0>;
+
+impl<T, const R: usize, const G: usize, const B: usize, const A: usize> From<[T; 4]>
+    for Rgba<T, R, G, B, A>
+{
+    fn from(value: [T; 4]) -> Self {
+        Rgba(value)
+    }
+}
+
+impl<T, const R: usize, const G: usize, const B: usize, const A: usize> Deref
+    for Rgba<T, R, G, B, A>
+{
+    type Target = [T; 4];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const R: usize, const G: usize, const B: usize, const A: usize> DerefMut
+    for Rgba<T, R, G, B, A>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, const R: usize, const G: usize, const B: usize, const A: usize> Pixel
+    for Rgba<T, R, G, B, A>
+{
+    const CHANNELS: u8 = 4;
+}
+
+macro_rules! impl_from_rgba {
+    (
+        $r1:literal, $g1:literal, $b1:literal, $a1:literal =>
+        $r2:literal, $g2:literal, $b2:literal, $a2:literal
+    ) => {
+        impl<T, U> From<Rgba<U, $r1, $g1, $b1, $a1>> for Rgba<T, $r2, $g2, $b2, $a2>
+        where
+            T: Copy + Default + From<U>,
+            U: Copy,
+        {
+            fn from(rgba: Rgba<U, $r1, $g1, $b1, $a1>) -> Self {
+                let mut out = Rgba::<T, $r2, $g2, $b2, $a2>::default();
+                out[$r2] = T::from(rgba[$r1]);
+                out[$g2] = T::from(rgba[$g1]);
+                out[$b2] = T::from(rgba[$b1]);
+                out[$a2] = T::from(rgba[$a1]);
+                out
+            }
+        }
+    };
+}
+
+// rgba <-> bgra, argb, abgr
+impl_from_rgba!(0, 1, 2, 3 => 2, 1, 0, 3);
+impl_from_rgba!(0, 1, 2, 3 => 1, 2, 3, 0);
+impl_from_rgba!(0, 1, 2, 3 => 3, 2, 1, 0);
+impl_from_rgba!(2, 1, 0, 3 => 0, 1, 2, 3);
+impl_from_rgba!(1, 2, 3, 0 => 0, 1, 2, 3);
+impl_from_rgba!(3, 2, 1, 0 => 0, 1, 2, 3);
+// bgra <-> argb, abgr
+impl_from_rgba!(2, 1, 0, 3 => 1, 2, 3, 0);
+impl_from_rgba!(2, 1, 0, 3 => 3, 2, 1, 0);
+impl_from_rgba!(1, 2, 3, 0 => 2, 1, 0, 3);
+impl_from_rgba!(3, 2, 1, 0 => 2, 1, 0, 3);
+// argb <-> abgr
+impl_from_rgba!(1, 2, 3, 0 => 3, 2, 1, 0);
+impl_from_rgba!(3, 2, 1, 0 => 1, 2, 3, 0);
+
+impl<
+        T,
+        const R: usize,
+        const G: usize,
+        const B: usize,
+        const A: usize,
+        const R2: usize,
+        const G2: usize,
+        const B2: usize,
+    > From<Rgba<T, R, G, B, A>> for Rgb<T, R2, G2, B2>
+where
+    T: Copy + Default,
+{
+    fn from(rgba: Rgba<T, R, G, B, A>) -> Self {
+        // the alpha channel is dropped
+        let mut rgb = Rgb::<T, R2, G2, B2>::default();
+        rgb[R2] = rgba[R];
+        rgb[G2] = rgba[G];
+        rgb[B2] = rgba[B];
+        rgb
+    }
+}
+
+impl<T, U, const R: usize, const G: usize, const B: usize, const A: usize> From<Rgba<U, R, G, B, A>>
+    for Gray<T>
+where
+    T: LumaSample<U>,
+    U: Copy,
+{
+    fn from(rgba: Rgba<U, R, G, B, A>) -> Self {
+        // the alpha channel is dropped
+        Gray::from(Rgb::<U>([rgba[R], rgba[G], rgba[B]]))
+    }
+}
+
+impl<
+        T,
+        const R: usize,
+        const G: usize,
+        const B: usize,
+        const R2: usize,
+        const G2: usize,
+        const B2: usize,
+        const A2: usize,
+    > From<Rgb<T, R, G, B>> for Rgba<T, R2, G2, B2, A2>
+where
+    T: Sample + Default,
+{
+    fn from(rgb: Rgb<T, R, G, B>) -> Self {
+        let mut rgba = Rgba::<T, R2, G2, B2, A2>::default();
+        rgba[R2] = rgb[R];
+        rgba[G2] = rgb[G];
+        rgba[B2] = rgb[B];
+        rgba[A2] = T::MAX;
+        rgba
+    }
+}
+
+impl<T, const R: usize, const G: usize, const B: usize, const A: usize> From<Gray<T>>
+    for Rgba<T, R, G, B, A>
+where
+    T: Sample + Default,
+{
+    fn from(gray: Gray<T>) -> Self {
+        let mut rgba = Rgba::<T, R, G, B, A>::default();
+        rgba[R] = gray[0];
+        rgba[G] = gray[0];
+        rgba[B] = gray[0];
+        rgba[A] = T::MAX;
+        rgba
+    }
+}
+
+impl<T, U, const R: usize, const G: usize, const B: usize, const A: usize>
+    FromDepth<Rgba<U, R, G, B, A>> for Rgba<T, R, G, B, A>
+where
+    T: Sample,
+    U: Sample,
+{
+    fn from_depth(rgba: Rgba<U, R, G, B, A>) -> Self {
+        Rgba([
+            rgba[0].to_sample(),
+            rgba[1].to_sample(),
+            rgba[2].to_sample(),
+            rgba[3].to_sample(),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Bgr;
+
+    #[test]
+    fn channels() {
+        assert_eq!(Rgba::<u8>::CHANNELS, 4);
+    }
+
+    #[test]
+    fn index_mut() {
+        let pix: Rgba<u8> = Rgba([1, 2, 3, 4]);
+
+        assert_eq!(pix.0[0], 1);
+        assert_eq!(pix.0[1], 2);
+        assert_eq!(pix.0[2], 3);
+        assert_eq!(pix.0[3], 4);
+    }
+
+    #[test]
+    fn reorder() {
+        let rgba = Rgba::<u8>([1, 2, 3, 4]);
+
+        assert_eq!(Bgra::<u8>::from(rgba), Rgba([3, 2, 1, 4]));
+        assert_eq!(Argb::<u8>::from(rgba), Rgba([4, 1, 2, 3]));
+        assert_eq!(Abgr::<u8>::from(rgba), Rgba([4, 3, 2, 1]));
+        assert_eq!(Argb::<u8>::from(Abgr::<u8>::from(rgba)), Rgba([4, 1, 2, 3]));
+        assert_eq!(Rgba::<u8>::from(Bgra::<u8>::from(rgba)), rgba);
+    }
+
+    #[test]
+    fn rgb() {
+        let bgr: Bgr<u8> = Rgb([3, 2, 1]);
+
+        assert_eq!(Argb::<u8>::from(bgr), Rgba([255, 1, 2, 3]));
+        assert_eq!(Rgba::<f32>::from(Rgb::<f32>([0.5, 0.5, 0.5]))[3], 1.0);
+        assert_eq!(Bgr::<u8>::from(Argb::<u8>::from([255, 1, 2, 3])), bgr);
+    }
+
+    #[test]
+    fn gray() {
+        assert_eq!(Bgra::<u16>::from(Gray([7])), Rgba([7, 7, 7, u16::MAX]));
+        assert_eq!(
+            Gray::<u8>::from(Abgr::<u8>::from([0, 10, 10, 10])),
+            Gray([10])
+        );
+        // different sample types
+        assert_eq!(Gray::<f32>::from(Rgba::<u8>([10, 10, 10, 0])), Gray([10.0]));
+        assert_eq!(Gray::<u16>::from(Bgra::<f32>::from([2.0; 4])), Gray([2]));
+    }
+}
@@ -0,0 +1,201 @@
+use num_traits::Float;
+
+use crate::sample::Sample;
+
+/// Transfer function between linear light and encoded (i.e. gamma corrected) values.
+///
+/// Values are normalized to the range of 0.0 to 1.0 on both sides.
+pub trait TransferFunction {
+    /// Returns the encoded value of a linear light value.
+    fn encode(linear: f32) -> f32;
+
+    /// Returns the linear light value of an encoded value.
+    fn decode(encoded: f32) -> f32;
+
+    /// Returns the linear light value of an 8-bit encoded value.
+    ///
+    /// Implementations may use a lookup table, the default calls [`TransferFunction::decode`].
+    fn decode_u8(encoded: u8) -> f32 {
+        Self::decode(encoded.to_normalized())
+    }
+}
+
+/// sRGB transfer function (IEC 61966-2-1)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Srgb;
+
+impl TransferFunction for Srgb {
+    fn encode(linear: f32) -> f32 {
+        if linear <= 0.0031308 {
+            linear * 12.92
+        } else {
+            1.055 * Float::powf(linear, 1.0 / 2.4) - 0.055
+        }
+    }
+
+    fn decode(encoded: f32) -> f32 {
+        if encoded <= 0.04045 {
+            encoded / 12.92
+        } else {
+            Float::powf((encoded + 0.055) / 1.055, 2.4)
+        }
+    }
+
+    fn decode_u8(encoded: u8) -> f32 {
+        SRGB_TO_LINEAR[encoded as usize]
+    }
+}
+
+/// ITU-R BT.709 opto-electronic transfer function (OETF)
+///
+/// This is the camera side curve. Decoding applies the inverse OETF, which recovers the scene
+/// light, not the display light: use [`Bt1886`] as the display EOTF of BT.709 content.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bt709Oetf;
+
+impl TransferFunction for Bt709Oetf {
+    fn encode(linear: f32) -> f32 {
+        if linear < 0.018 {
+            linear * 4.5
+        } else {
+            1.099 * Float::powf(linear, 0.45) - 0.099
+        }
+    }
+
+    fn decode(encoded: f32) -> f32 {
+        if encoded < 0.081 {
+            encoded / 4.5
+        } else {
+            Float::powf((encoded + 0.099) / 1.099, 1.0 / 0.45)
+        }
+    }
+}
+
+/// ITU-R BT.1886 reference electro-optical transfer function (EOTF)
+///
+/// This is a pure 2.4 gamma curve, assuming a display with zero black level.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bt1886;
+
+impl TransferFunction for Bt1886 {
+    fn encode(linear: f32) -> f32 {
+        Float::powf(linear.max(0.0), 1.0 / 2.4)
+    }
+
+    fn decode(encoded: f32) -> f32 {
+        Float::powf(encoded.max(0.0), 2.4)
+    }
+}
+
+/// Sample types which can be converted to and from linear light.
+///
+/// 8-bit samples use [`TransferFunction::decode_u8`], which allows for table lookups.
+pub trait TransferSample: Sample {
+    /// Returns the linear light value of the sample.
+    fn decode<F: TransferFunction>(self) -> f32 {
+        F::decode(self.to_normalized())
+    }
+
+    /// Returns the sample of a linear light value.
+    fn encode<F: TransferFunction>(linear: f32) -> Self {
+        Self::from_normalized(F::encode(linear))
+    }
+}
+
+impl TransferSample for u8 {
+    fn decode<F: TransferFunction>(self) -> f32 {
+        F::decode_u8(self)
+    }
+}
+
+impl TransferSample for u16 {}
+
+impl TransferSample for f32 {}
+
+impl<const N: u32> TransferSample for crate::sample::Bits<N> {}
+
+#[cfg(feature = "f16")]
+impl TransferSample for crate::sample::f16 {}
+
+/// Linear light values of all 8-bit sRGB encoded values
+#[rustfmt::skip]
+const SRGB_TO_LINEAR: [f32; 256] = [
+    0.0, 0.000303527, 0.000607054, 0.000910581, 0.001214108, 0.001517635, 0.001821162,
+    0.0021246888, 0.002428216, 0.0027317428, 0.00303527, 0.0033465358, 0.0036765074, 0.004024717,
+    0.004391442, 0.0047769533, 0.0051815165, 0.0056053917, 0.006048833, 0.0065120906, 0.00699541,
+    0.007499032, 0.008023193, 0.008568126, 0.009134059, 0.009721218, 0.010329823, 0.010960094,
+    0.011612245, 0.012286488, 0.0129830325, 0.013702083, 0.014443844, 0.015208514, 0.015996294,
+    0.016807375, 0.017641954, 0.01850022, 0.019382361, 0.020288562, 0.02121901, 0.022173885,
+    0.023153367, 0.024157632, 0.02518686, 0.026241222, 0.027320892, 0.02842604, 0.029556835,
+    0.030713445, 0.031896032, 0.033104766, 0.034339808, 0.035601314, 0.03688945, 0.038204372,
+    0.039546236, 0.0409152, 0.04231141, 0.04373503, 0.045186203, 0.046665087, 0.048171826,
+    0.049706567, 0.051269457, 0.052860647, 0.054480277, 0.05612849, 0.05780543, 0.059511237,
+    0.061246052, 0.063010015, 0.064803265, 0.06662594, 0.06847817, 0.070360094, 0.07227185,
+    0.07421357, 0.07618538, 0.07818742, 0.08021982, 0.08228271, 0.08437621, 0.08650046, 0.08865558,
+    0.09084171, 0.093058966, 0.09530747, 0.09758735, 0.099898726, 0.10224173, 0.104616486,
+    0.107023105, 0.10946171, 0.11193243, 0.114435375, 0.116970666, 0.11953843, 0.122138776,
+    0.12477182, 0.12743768, 0.13013647, 0.13286832, 0.13563333, 0.13843161, 0.14126329, 0.14412847,
+    0.14702727, 0.14995979, 0.15292615, 0.15592647, 0.15896083, 0.16202937, 0.1651322, 0.1682694,
+    0.17144111, 0.1746474, 0.17788842, 0.18116425, 0.18447499, 0.18782078, 0.19120169, 0.19461784,
+    0.19806932, 0.20155625, 0.20507874, 0.20863687, 0.21223076, 0.2158605, 0.2195262, 0.22322796,
+    0.22696587, 0.23074006, 0.23455058, 0.23839757, 0.24228112, 0.24620132, 0.25015828, 0.2541521,
+    0.25818285, 0.26225066, 0.2663556, 0.2704978, 0.2746773, 0.27889428, 0.28314874, 0.28744084,
+    0.29177064, 0.29613826, 0.30054379, 0.3049873, 0.30946892, 0.31398872, 0.31854677, 0.3231432,
+    0.3277781, 0.33245152, 0.33716363, 0.34191442, 0.34670407, 0.3515326, 0.35640013, 0.3613068,
+    0.3662526, 0.3712377, 0.37626213, 0.38132602, 0.38642943, 0.39157248, 0.39675522, 0.40197778,
+    0.4072402, 0.4125426, 0.41788507, 0.42326766, 0.4286905, 0.43415365, 0.43965718, 0.4452012,
+    0.4507858, 0.45641103, 0.462077, 0.4677838, 0.47353148, 0.47932017, 0.48514995, 0.49102086,
+    0.49693298, 0.5028865, 0.50888133, 0.5149177, 0.52099556, 0.5271151, 0.5332764, 0.5394795,
+    0.54572445, 0.55201143, 0.5583404, 0.5647115, 0.57112485, 0.57758045, 0.58407843, 0.59061885,
+    0.59720176, 0.60382736, 0.61049557, 0.6172066, 0.6239604, 0.63075715, 0.63759685, 0.6444797,
+    0.65140563, 0.65837485, 0.6653873, 0.67244315, 0.6795425, 0.6866853, 0.69387174, 0.7011019,
+    0.70837575, 0.7156935, 0.7230551, 0.73046076, 0.7379104, 0.7454042, 0.7529422, 0.7605245,
+    0.76815116, 0.7758222, 0.7835378, 0.7912979, 0.7991027, 0.80695224, 0.8148466, 0.82278574,
+    0.8307699, 0.838799, 0.8468732, 0.8549926, 0.8631572, 0.8713671, 0.8796224, 0.8879231,
+    0.8962694, 0.9046612, 0.91309863, 0.92158186, 0.9301109, 0.9386857, 0.9473065, 0.9559733,
+    0.9646863, 0.9734453, 0.9822506, 0.9911021, 1.0,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<F: TransferFunction>() {
+        for i in 0..=1000 {
+            let linear = i as f32 / 1000.0;
+            assert!((F::decode(F::encode(linear)) - linear).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn srgb() {
+        assert_eq!(Srgb::encode(0.0), 0.0);
+        assert!((Srgb::encode(1.0) - 1.0).abs() < 1e-6);
+        assert!((Srgb::encode(0.214) - 0.5).abs() < 1e-3);
+        round_trip::<Srgb>();
+    }
+
+    #[test]
+    fn srgb_table() {
+        for v in 0..=255u8 {
+            assert!((Srgb::decode_u8(v) - Srgb::decode(v.to_normalized())).abs() < 1e-6);
+            assert_eq!(u8::encode::<Srgb>(v.decode::<Srgb>()), v);
+        }
+    }
+
+    #[test]
+    fn bt709() {
+        assert!((Bt709Oetf::encode(0.01) - 0.045).abs() < 1e-6);
+        assert!((Bt709Oetf::encode(1.0) - 1.0).abs() < 1e-6);
+        round_trip::<Bt709Oetf>();
+    }
+
+    #[test]
+    fn bt1886() {
+        assert!((Bt1886::decode(0.5) - 0.18946).abs() < 1e-5);
+        round_trip::<Bt1886>();
+        for v in 0..=255u8 {
+            assert_eq!(u8::encode::<Bt1886>(v.decode::<Bt1886>()), v);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use core::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    color::{
+        rgb::Rgb,
+        transfer::{Srgb, TransferSample},
+    },
+    Pixel,
+};
+
+/// Reference white of a color space.
+pub trait WhitePoint {
+    /// XYZ tristimulus values of the white point, normalized to a luminance (Y) of 1.0
+    const XYZ: [f32; 3];
+}
+
+/// CIE standard illuminant D65 (noon daylight), the white point of sRGB
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct D65;
+
+impl WhitePoint for D65 {
+    const XYZ: [f32; 3] = [0.95047, 1.0, 1.08883];
+}
+
+/// CIE standard illuminant D50 (horizon light), the white point of ICC profiles
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct D50;
+
+impl WhitePoint for D50 {
+    const XYZ: [f32; 3] = [0.96422, 1.0, 0.82521];
+}
+
+/// CIE 1931 XYZ pixel
+///
+/// Tristimulus values are relative to the white point `W`, which has a luminance (Y) of 1.0.
+/// Conversions from and to `Rgb` treat the RGB samples as sRGB encoded and perform a Bradford
+/// chromatic adaptation if `W` is not [`D65`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Xyz<T, W = D65>(pub [T; 3], PhantomData<W>);
+
+impl<T, W> From<[T; 3]> for Xyz<T, W> {
+    fn from(value: [T; 3]) -> Self {
+        Xyz(value, PhantomData)
+    }
+}
+
+impl<T, W> Deref for Xyz<T, W> {
+    type Target = [T; 3];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, W> DerefMut for Xyz<T, W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, W> Pixel for Xyz<T, W> {
+    const CHANNELS: u8 = 3;
+}
+
+/// Linear sRGB to XYZ (D65)
+const RGB_TO_XYZ: [[f32; 3]; 3] = [
+    [0.4124564, 0.3575761, 0.1804375],
+    [0.2126729, 0.7151522, 0.072175],
+    [0.0193339, 0.119192, 0.9503041],
+];
+
+/// XYZ (D65) to linear sRGB
+const XYZ_TO_RGB: [[f32; 3]; 3] = [
+    [3.2404542, -1.5371385, -0.4985314],
+    [-0.969266, 1.8760108, 0.0415560],
+    [0.0556434, -0.2040259, 1.0572252],
+];
+
+/// XYZ to Bradford cone response
+const BRADFORD: [[f32; 3]; 3] = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+/// Bradford cone response to XYZ
+const BRADFORD_INV: [[f32; 3]; 3] = [
+    [0.9869929, -0.1470543, 0.1599627],
+    [0.4323053, 0.5183603, 0.0492912],
+    [-0.0085287, 0.0400428, 0.9684867],
+];
+
+fn mul(m: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+/// Returns XYZ values relative to `W2` for XYZ values relative to `W1`.
+fn adapt<W1: WhitePoint, W2: WhitePoint>(xyz: [f32; 3]) -> [f32; 3] {
+    if W1::XYZ == W2::XYZ {
+        return xyz;
+    }
+
+    let src = mul(&BRADFORD, W1::XYZ);
+    let dst = mul(&BRADFORD, W2::XYZ);
+    let lms = mul(&BRADFORD, xyz);
+    let lms = [
+        lms[0] * dst[0] / src[0],
+        lms[1] * dst[1] / src[1],
+        lms[2] * dst[2] / src[2],
+    ];
+    mul(&BRADFORD_INV, lms)
+}
+
+impl<W: WhitePoint> Xyz<f32, W> {
+    /// Returns the XYZ pixel of linear light sRGB values.
+    pub fn from_linear<const R: usize, const G: usize, const B: usize>(
+        rgb: Rgb<f32, R, G, B>,
+    ) -> Self {
+        let xyz = mul(&RGB_TO_XYZ, [rgb[R], rgb[G], rgb[B]]);
+        Xyz::from(adapt::<D65, W>(xyz))
+    }
+
+    /// Returns the linear light sRGB values of the pixel.
+    ///
+    /// Colors outside of the sRGB gamut yield values outside of the range of 0.0 to 1.0.
+    pub fn to_linear<const R: usize, const G: usize, const B: usize>(self) -> Rgb<f32, R, G, B> {
+        let rgb = mul(&XYZ_TO_RGB, adapt::<W, D65>(self.0));
+
+        let mut out = Rgb::<f32, R, G, B>::default();
+        out[R] = rgb[0];
+        out[G] = rgb[1];
+        out[B] = rgb[2];
+        out
+    }
+
+    /// Returns the pixel adapted to another white point.
+    pub fn adapt<W2: WhitePoint>(self) -> Xyz<f32, W2> {
+        Xyz::from(adapt::<W, W2>(self.0))
+    }
+}
+
+impl<T, W, const R: usize, const G: usize, const B: usize> From<Rgb<T, R, G, B>> for Xyz<f32, W>
+where
+    T: TransferSample,
+    W: WhitePoint,
+{
+    fn from(rgb: Rgb<T, R, G, B>) -> Self {
+        Xyz::from_linear(Rgb::<f32>([
+            rgb[R].decode::<Srgb>(),
+            rgb[G].decode::<Srgb>(),
+            rgb[B].decode::<Srgb>(),
+        ]))
+    }
+}
+
+impl<T, W, const R: usize, const G: usize, const B: usize> From<Xyz<f32, W>> for Rgb<T, R, G, B>
+where
+    T: TransferSample + Default,
+    W: WhitePoint,
+{
+    fn from(xyz: Xyz<f32, W>) -> Self {
+        let linear: Rgb<f32> = xyz.to_linear();
+
+        let mut rgb = Rgb::<T, R, G, B>::default();
+        rgb[R] = T::encode::<Srgb>(linear[0]);
+        rgb[G] = T::encode::<Srgb>(linear[1]);
+        rgb[B] = T::encode::<Srgb>(linear[2]);
+        rgb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: [f32; 3], b: [f32; 3], delta: f32) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < delta, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn channels() {
+        assert_eq!(Xyz::<f32>::CHANNELS, 3);
+    }
+
+    #[test]
+    fn from_rgb() {
+        let white = Xyz::<f32>::from(Rgb::<u8>([255, 255, 255]));
+        assert_near(*white, D65::XYZ, 1e-4);
+
+        let white = Xyz::<f32, D50>::from(Rgb::<u8>([255, 255, 255]));
+        assert_near(*white, D50::XYZ, 1e-4);
+
+        let red = Xyz::<f32>::from(Rgb::<u8>([255, 0, 0]));
+        assert_near(*red, [0.4124564, 0.2126729, 0.0193339], 1e-6);
+    }
+
+    #[test]
+    fn to_rgb() {
+        let xyz = Xyz::<f32, D50>::from([0.2, 0.3, 0.4]);
+        assert_near(*xyz.adapt::<D65>().adapt::<D50>(), *xyz, 1e-5);
+
+        for rgb in [[255, 0, 0], [0, 128, 0], [10, 20, 30], [255, 255, 255]] {
+            let rgb = Rgb::<u8>(rgb);
+            assert_eq!(Rgb::<u8>::from(Xyz::<f32>::from(rgb)), rgb);
+            assert_eq!(Rgb::<u8>::from(Xyz::<f32, D50>::from(rgb)), rgb);
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Bulk conversion of whole buffers.
+//!
+//! In contrast to the iterator adapters, [`convert`] validates the buffer lengths up front and
+//! reports mismatches as [`Error`] instead of panicking:
+//!
+//! ```
+//! use ffimage::{color::{Gray, Rgb}, convert::convert, Error};
+//!
+//! let rgb = [10u8, 20, 30, 40, 50, 60];
+//! let mut gray = [0u8; 2];
+//! convert::<Rgb<u8>, Gray<u8>>(&rgb, &mut gray).unwrap();
+//!
+//! let mut short = [0u8; 1];
+//! assert_eq!(
+//!     convert::<Rgb<u8>, Gray<u8>>(&rgb, &mut short),
+//!     Err(Error::LengthMismatch { expected: 2, actual: 1 })
+//! );
+//! ```
+
+use core::{any::TypeId, convert::TryFrom, ops::Deref, slice};
+
+use crate::{
+    color::{Bgr, DefaultLuma, Gray, Rgb},
+    simd, Error,
+};
+
+/// Conversion of a buffer of `Self` pixels into a buffer of `D` pixels.
+///
+/// The trait is automatically implemented for all pixel types which implement `From<[T; C]>`
+/// and `Deref<Target = [T; C]>`, where `D` implements `From<Self>`. The pixels are converted
+/// using these `From` implementations unless a [`ConvertKernel`] handles the pair of types.
+pub trait Convert<D> {
+    /// Sample type of the source pixels
+    type Src;
+    /// Sample type of the destination pixels
+    type Dst;
+
+    /// Converts the pixels of `src` and writes them to `dst`, using the [`SimdKernel`].
+    ///
+    /// Fails without touching `dst` if `src` does not hold whole pixels or if `dst` does not
+    /// hold exactly as many pixels as `src`.
+    fn convert(src: &[Self::Src], dst: &mut [Self::Dst]) -> Result<(), Error> {
+        Self::convert_with::<SimdKernel>(src, dst)
+    }
+
+    /// Converts the pixels of `src` and writes them to `dst`, trying the kernel `K` first.
+    ///
+    /// See [`convert`](Self::convert).
+    fn convert_with<K: ConvertKernel>(
+        src: &[Self::Src],
+        dst: &mut [Self::Dst],
+    ) -> Result<(), Error>;
+}
+
+/// Converts the pixels of `src` and writes them to `dst`.
+///
+/// See [`Convert::convert`].
+pub fn convert<S, D>(src: &[S::Src], dst: &mut [S::Dst]) -> Result<(), Error>
+where
+    S: Convert<D>,
+{
+    S::convert(src, dst)
+}
+
+/// Converts the pixels of `src` and writes them to `dst`, trying the kernel `K` first.
+///
+/// See [`Convert::convert_with`].
+pub fn convert_with<S, D, K>(src: &[S::Src], dst: &mut [S::Dst]) -> Result<(), Error>
+where
+    S: Convert<D>,
+    K: ConvertKernel,
+{
+    S::convert_with::<K>(src, dst)
+}
+
+/// Specialized conversion of pixels with 8 bit samples, e.g. a SIMD kernel.
+///
+/// Kernels are the extension point of [`Convert`]: other crates implement this trait for the
+/// pixel types they know fast paths for and pass it to [`convert_with`]. A kernel has to produce
+/// the same results as the `From` implementations. Tuples of kernels try each one in turn, `()`
+/// handles no pair of types at all.
+pub trait ConvertKernel {
+    /// Converts the pixels of `src` from `S` to `D`, returns false if the pair is not handled.
+    ///
+    /// The buffer lengths are validated by the caller.
+    fn convert<S: 'static, D: 'static>(src: &[u8], dst: &mut [u8]) -> bool;
+}
+
+impl ConvertKernel for () {
+    fn convert<S: 'static, D: 'static>(_src: &[u8], _dst: &mut [u8]) -> bool {
+        false
+    }
+}
+
+impl<A: ConvertKernel, B: ConvertKernel> ConvertKernel for (A, B) {
+    fn convert<S: 'static, D: 'static>(src: &[u8], dst: &mut [u8]) -> bool {
+        A::convert::<S, D>(src, dst) || B::convert::<S, D>(src, dst)
+    }
+}
+
+fn is<A: 'static, B: 'static>() -> bool {
+    TypeId::of::<A>() == TypeId::of::<B>()
+}
+
+/// Kernels of the [`simd`] module
+///
+/// Handles `Rgb<u8>` to `Bgr<u8>` and back as well as `Rgb<u8>` to `Gray<u8>` (with
+/// [`DefaultLuma`], just like `Gray::from`).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SimdKernel;
+
+impl ConvertKernel for SimdKernel {
+    fn convert<S: 'static, D: 'static>(src: &[u8], dst: &mut [u8]) -> bool {
+        if (is::<S, Rgb<u8>>() && is::<D, Bgr<u8>>()) || (is::<S, Bgr<u8>>() && is::<D, Rgb<u8>>())
+        {
+            simd::rgb_to_bgr(src, dst);
+        } else if is::<S, Rgb<u8>>() && is::<D, Gray<u8>>() {
+            simd::rgb_to_gray::<DefaultLuma>(src, dst);
+        } else {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// Runs the kernel `K` if both sample types are `u8`, returns false if it did not convert.
+fn kernel<K: ConvertKernel, S: 'static, D: 'static, T: 'static, U: 'static>(
+    src: &[T],
+    dst: &mut [U],
+) -> bool {
+    if !is::<T, u8>() || !is::<U, u8>() {
+        return false;
+    }
+
+    // SAFETY: both sample types are u8
+    let src = unsafe { slice::from_raw_parts(src.as_ptr() as *const u8, src.len()) };
+    let dst = unsafe { slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u8, dst.len()) };
+
+    K::convert::<S, D>(src, dst)
+}
+
+impl<S, D, T, U, const C: usize, const N: usize> Convert<D> for S
+where
+    S: From<[T; C]> + Deref<Target = [T; C]> + 'static,
+    D: From<S> + Deref<Target = [U; N]> + 'static,
+    T: Copy + 'static,
+    U: Copy + 'static,
+{
+    type Src = T;
+    type Dst = U;
+
+    fn convert_with<K: ConvertKernel>(src: &[T], dst: &mut [U]) -> Result<(), Error> {
+        if src.len() % C != 0 {
+            return Err(Error::PartialPixel {
+                channels: C,
+                actual: src.len(),
+            });
+        }
+        if dst.len() != src.len() / C * N {
+            return Err(Error::LengthMismatch {
+                expected: src.len() / C * N,
+                actual: dst.len(),
+            });
+        }
+
+        if kernel::<K, S, D, T, U>(src, dst) {
+            return Ok(());
+        }
+
+        for (src, dst) in src.chunks_exact(C).zip(dst.chunks_exact_mut(N)) {
+            let pix = S::from(<[T; C]>::try_from(src).unwrap());
+            dst.copy_from_slice(&*D::from(pix));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{Rgba, Xyz};
+
+    #[test]
+    fn kernel() {
+        let rgb = [10u8, 20, 30, 255, 0, 128];
+
+        let mut bgr = [0u8; 6];
+        convert::<Rgb<u8>, Bgr<u8>>(&rgb, &mut bgr).unwrap();
+        assert_eq!(bgr, [30, 20, 10, 128, 0, 255]);
+
+        let mut gray = [0u8; 2];
+        convert::<Rgb<u8>, Gray<u8>>(&rgb, &mut gray).unwrap();
+        assert_eq!(
+            gray,
+            [
+                Gray::<u8>::from(Rgb::<u8>([10, 20, 30]))[0],
+                Gray::<u8>::from(Rgb::<u8>([255, 0, 128]))[0]
+            ]
+        );
+    }
+
+    #[test]
+    fn kernel_matches_from() {
+        // the SIMD kernels and the From implementations use the same luma coefficients
+        let rgb: [u8; 3 * 256] = core::array::from_fn(|i| (i * 97 + i / 7) as u8);
+
+        let mut simd = [0u8; 256];
+        let mut scalar = [0u8; 256];
+        convert::<Rgb<u8>, Gray<u8>>(&rgb, &mut simd).unwrap();
+        convert_with::<Rgb<u8>, Gray<u8>, ()>(&rgb, &mut scalar).unwrap();
+        assert_eq!(simd, scalar);
+    }
+
+    #[test]
+    fn custom_kernel() {
+        // kernel of another crate, its results differ from the From path to tell them apart
+        struct Invert;
+
+        impl ConvertKernel for Invert {
+            fn convert<S: 'static, D: 'static>(src: &[u8], dst: &mut [u8]) -> bool {
+                if !is::<S, Rgb<u8>>() || !is::<D, Rgba<u8>>() {
+                    return false;
+                }
+                for (src, dst) in src.chunks_exact(3).zip(dst.chunks_exact_mut(4)) {
+                    dst.copy_from_slice(&[!src[0], !src[1], !src[2], 0]);
+                }
+                true
+            }
+        }
+
+        let rgb = [10u8, 20, 30];
+        let mut rgba = [0u8; 4];
+        convert_with::<Rgb<u8>, Rgba<u8>, (SimdKernel, Invert)>(&rgb, &mut rgba).unwrap();
+        assert_eq!(rgba, [245, 235, 225, 0]);
+
+        // pairs the kernel does not handle fall through
+        let mut bgr = [0u8; 3];
+        convert_with::<Rgb<u8>, Bgr<u8>, (Invert, SimdKernel)>(&rgb, &mut bgr).unwrap();
+        assert_eq!(bgr, [30, 20, 10]);
+        convert_with::<Rgb<u8>, Bgr<u8>, Invert>(&rgb, &mut bgr).unwrap();
+        assert_eq!(bgr, [30, 20, 10]);
+    }
+
+    #[test]
+    fn fallback() {
+        let rgb = [10u16, 20, 30, 255, 0, 128];
+        let mut rgba = [0u16; 8];
+        convert::<Rgb<u16>, Rgba<u16>>(&rgb, &mut rgba).unwrap();
+        assert_eq!(rgba, [10, 20, 30, u16::MAX, 255, 0, 128, u16::MAX]);
+
+        let rgb = [255u8, 0, 0];
+        let mut xyz = [0f32; 3];
+        convert::<Rgb<u8>, Xyz<f32>>(&rgb, &mut xyz).unwrap();
+        assert_eq!(xyz, *Xyz::<f32>::from(Rgb::<u8>([255, 0, 0])));
+    }
+
+    #[test]
+    fn length_mismatch() {
+        let mut gray = [0u8; 2];
+        assert_eq!(
+            convert::<Rgb<u8>, Gray<u8>>(&[0; 7], &mut gray),
+            Err(Error::PartialPixel {
+                channels: 3,
+                actual: 7
+            })
+        );
+        assert_eq!(
+            convert::<Rgb<u8>, Gray<u8>>(&[0; 9], &mut gray),
+            Err(Error::LengthMismatch {
+                expected: 3,
+                actual: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use core::fmt;
+
+use crate::format::PixelFormat;
+
+/// Errors reported by the fallible APIs of this crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A buffer does not hold the expected number of samples
+    LengthMismatch {
+        /// Number of samples the buffer was expected to hold
+        expected: usize,
+        /// Number of samples the buffer actually holds
+        actual: usize,
+    },
+    /// A buffer does not hold whole pixels
+    PartialPixel {
+        /// Number of samples per pixel, the buffer length must be a multiple of it
+        channels: usize,
+        /// Number of samples the buffer actually holds
+        actual: usize,
+    },
+    /// A sample value cannot be represented by the target sample type
+    OutOfRange,
+    /// The frame dimensions do not fit the chroma subsampling of the pixel format or the
+    /// dimensions of the other frame of a conversion
+    InvalidDimensions {
+        /// Frame width in pixels
+        width: u32,
+        /// Frame height in pixels
+        height: u32,
+    },
+    /// The pixel format is not supported by the operation
+    Unsupported(PixelFormat),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::LengthMismatch { expected, actual } => write!(
+                f,
+                "length mismatch: expected {} samples, got {}",
+                expected, actual
+            ),
+            Error::PartialPixel { channels, actual } => write!(
+                f,
+                "partial pixel: expected a multiple of {} samples, got {}",
+                channels, actual
+            ),
+            Error::OutOfRange => write!(f, "sample value out of range of the target type"),
+            Error::InvalidDimensions { width, height } => {
+                write!(f, "invalid frame dimensions {}x{}", width, height)
+            }
+            Error::Unsupported(fmt) => write!(f, "unsupported pixel format {:?}", fmt),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
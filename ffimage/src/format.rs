@@ -0,0 +1,847 @@
+//! Runtime pixel format descriptors and dynamic conversion.
+//!
+//! All other APIs of this crate select pixel types at compile time. When the format of a buffer
+//! is only known at runtime (e.g. negotiated with a camera driver), [`PixelFormat`] describes it
+//! and [`convert`] dispatches to the typed conversion paths:
+//!
+//! ```
+//! use ffimage::format::{convert, PixelFormat};
+//!
+//! let bgra = [30u8, 20, 10, 255, 60, 50, 40, 255];
+//! let mut rgb = [0u8; 6];
+//! convert(PixelFormat::Bgra32, &bgra, PixelFormat::Rgb24, &mut rgb, 2, 1).unwrap();
+//!
+//! assert_eq!(rgb, [10, 20, 30, 40, 50, 60]);
+//! ```
+//!
+//! Frames with padded rows are described by [`DynamicImageView`], [`DynamicImageViewMut`] and
+//! `DynamicImageBuffer` and converted by [`convert_image`].
+//!
+//! All pairs are converted through the 16 bit [`Hub`] pixel. Conversions between formats which
+//! only reorder, expand or drop channels are exact. Formats with 8 bit channels are converted
+//! back to 8 bit RGB before their color model (e.g. grayscale or HSV) is applied, so their
+//! results match the typed `From` implementations no matter which format they came from. The
+//! YUV formats are described here, but converted by the `ffimage_yuv::format` module.
+//!
+//! Not every pixel type of this crate has a runtime format yet. The following ones are only
+//! available through the typed APIs:
+//!
+//! * Floating point samples (`f32`, `f64` and `f16`) of all color types
+//! * Bayer color filter arrays ([`crate::bayer`])
+//! * MIPI RAW10 and RAW12 packed samples ([`crate::iter::UnpackRawExt`],
+//!   [`crate::iter::PackRawExt`])
+//! * [`Hsv`] and [`Hsl`] with a hue range of 0..180 ([`crate::color::Hsv180`],
+//!   [`crate::color::Hsl180`])
+//! * [`crate::color::Lab`], [`crate::color::Luv`], [`crate::color::Xyz`] and
+//!   [`crate::color::LinearRgb`]
+
+use crate::{
+    color::{
+        Abgr, Argb, Argb1555, Bgr, Bgr10a2, Bgr565, Bgra, DefaultLuma, Gray, GrayAlpha, Hsl, Hsv,
+        Rgb, Rgb10a2, Rgb555, Rgb565, Rgba, X2Bgr10, X2Rgb10,
+    },
+    image::{DynamicImageView, DynamicImageViewMut},
+    sample::Sample,
+    simd, Error,
+};
+
+/// Pixel format of a buffer
+///
+/// Samples wider than 8 bits are stored in native byte order, packed formats in little-endian
+/// order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PixelFormat {
+    /// [`Gray<u8>`]
+    Gray8,
+    /// [`Gray<u16>`]
+    Gray16,
+    /// [`GrayAlpha<u8>`]
+    GrayAlpha8,
+    /// [`GrayAlpha<u16>`]
+    GrayAlpha16,
+    /// [`Rgb<u8>`]
+    Rgb24,
+    /// [`Bgr<u8>`]
+    Bgr24,
+    /// [`Rgb<u16>`]
+    Rgb48,
+    /// [`Bgr<u16>`]
+    Bgr48,
+    /// [`Rgba<u8>`]
+    Rgba32,
+    /// [`Bgra<u8>`]
+    Bgra32,
+    /// [`Argb<u8>`]
+    Argb32,
+    /// [`Abgr<u8>`]
+    Abgr32,
+    /// [`Rgba<u16>`]
+    Rgba64,
+    /// [`Bgra<u16>`]
+    Bgra64,
+    /// [`Rgb565`]
+    Rgb565,
+    /// [`Bgr565`]
+    Bgr565,
+    /// [`Rgb555`]
+    Rgb555,
+    /// [`Argb1555`]
+    Argb1555,
+    /// [`Rgb10a2`]
+    Rgb10a2,
+    /// [`Bgr10a2`]
+    Bgr10a2,
+    /// [`X2Rgb10`]
+    X2Rgb10,
+    /// [`X2Bgr10`]
+    X2Bgr10,
+    /// [`Hsv<u8>`]
+    Hsv24,
+    /// [`Hsl<u8>`]
+    Hsl24,
+    /// Packed YUV 4:4:4, `ffimage_yuv::yuv::Yuv<u8>`
+    Yuv444,
+    /// Packed YUV 4:2:2, `ffimage_yuv::yuv422::Yuyv<u8>`
+    Yuyv,
+    /// Packed YUV 4:2:2, `ffimage_yuv::yuv422::Uyvy<u8>`
+    Uyvy,
+    /// Planar YUV 4:2:0 with the Y, U and V planes following each other
+    Yuv420p,
+}
+
+/// Chroma subsampling of a pixel format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Subsampling {
+    /// Every pixel has its own color channels
+    None,
+    /// Chroma has half the horizontal resolution, the width must be even
+    Yuv422,
+    /// Chroma has half the horizontal and vertical resolution, width and height must be even
+    Yuv420,
+}
+
+impl PixelFormat {
+    /// All pixel formats
+    pub const ALL: &'static [PixelFormat] = &[
+        PixelFormat::Gray8,
+        PixelFormat::Gray16,
+        PixelFormat::GrayAlpha8,
+        PixelFormat::GrayAlpha16,
+        PixelFormat::Rgb24,
+        PixelFormat::Bgr24,
+        PixelFormat::Rgb48,
+        PixelFormat::Bgr48,
+        PixelFormat::Rgba32,
+        PixelFormat::Bgra32,
+        PixelFormat::Argb32,
+        PixelFormat::Abgr32,
+        PixelFormat::Rgba64,
+        PixelFormat::Bgra64,
+        PixelFormat::Rgb565,
+        PixelFormat::Bgr565,
+        PixelFormat::Rgb555,
+        PixelFormat::Argb1555,
+        PixelFormat::Rgb10a2,
+        PixelFormat::Bgr10a2,
+        PixelFormat::X2Rgb10,
+        PixelFormat::X2Bgr10,
+        PixelFormat::Hsv24,
+        PixelFormat::Hsl24,
+        PixelFormat::Yuv444,
+        PixelFormat::Yuyv,
+        PixelFormat::Uyvy,
+        PixelFormat::Yuv420p,
+    ];
+
+    /// Returns the number of color and alpha channels, not counting padding bits.
+    pub fn channels(self) -> u8 {
+        use PixelFormat::*;
+
+        match self {
+            Gray8 | Gray16 => 1,
+            GrayAlpha8 | GrayAlpha16 => 2,
+            Rgb24 | Bgr24 | Rgb48 | Bgr48 | Rgb565 | Bgr565 | Rgb555 | X2Rgb10 | X2Bgr10
+            | Hsv24 | Hsl24 | Yuv444 | Yuyv | Uyvy | Yuv420p => 3,
+            Rgba32 | Bgra32 | Argb32 | Abgr32 | Rgba64 | Bgra64 | Argb1555 | Rgb10a2 | Bgr10a2 => 4,
+        }
+    }
+
+    /// Returns the number of bits of the widest channel.
+    pub fn bits_per_sample(self) -> u8 {
+        use PixelFormat::*;
+
+        match self {
+            Gray16 | GrayAlpha16 | Rgb48 | Bgr48 | Rgba64 | Bgra64 => 16,
+            Rgb10a2 | Bgr10a2 | X2Rgb10 | X2Bgr10 => 10,
+            Rgb565 | Bgr565 => 6,
+            Rgb555 | Argb1555 => 5,
+            _ => 8,
+        }
+    }
+
+    /// Returns the average number of bits per pixel, including padding bits and subsampled
+    /// chroma.
+    pub fn bits_per_pixel(self) -> u8 {
+        use PixelFormat::*;
+
+        match self {
+            Gray8 => 8,
+            Gray16 | GrayAlpha8 | Rgb565 | Bgr565 | Rgb555 | Argb1555 | Yuyv | Uyvy => 16,
+            Yuv420p => 12,
+            Rgb24 | Bgr24 | Hsv24 | Hsl24 | Yuv444 => 24,
+            GrayAlpha16 | Rgba32 | Bgra32 | Argb32 | Abgr32 | Rgb10a2 | Bgr10a2 | X2Rgb10
+            | X2Bgr10 => 32,
+            Rgb48 | Bgr48 => 48,
+            Rgba64 | Bgra64 => 64,
+        }
+    }
+
+    /// Returns the chroma subsampling.
+    pub fn subsampling(self) -> Subsampling {
+        match self {
+            PixelFormat::Yuyv | PixelFormat::Uyvy => Subsampling::Yuv422,
+            PixelFormat::Yuv420p => Subsampling::Yuv420,
+            _ => Subsampling::None,
+        }
+    }
+
+    /// Returns the number of memory planes.
+    pub fn planes(self) -> u8 {
+        match self {
+            PixelFormat::Yuv420p => 3,
+            _ => 1,
+        }
+    }
+
+    /// Returns the number of bytes of a frame.
+    ///
+    /// Fails if the dimensions do not fit the chroma subsampling.
+    pub fn frame_len(self, width: u32, height: u32) -> Result<usize, Error> {
+        let valid = match self.subsampling() {
+            Subsampling::None => true,
+            Subsampling::Yuv422 => width % 2 == 0,
+            Subsampling::Yuv420 => width % 2 == 0 && height % 2 == 0,
+        };
+        if !valid {
+            return Err(Error::InvalidDimensions { width, height });
+        }
+
+        Ok(width as usize * height as usize * self.bits_per_pixel() as usize / 8)
+    }
+
+    /// Returns the number of bytes of a row of `width` pixels without padding.
+    ///
+    /// Returns `None` for planar formats, whose rows are split across planes, and if the width
+    /// does not fit the chroma subsampling.
+    pub fn row_len(self, width: u32) -> Option<usize> {
+        if self.planes() > 1 || (self.subsampling() == Subsampling::Yuv422 && width % 2 != 0) {
+            return None;
+        }
+
+        (width as usize)
+            .checked_mul(self.bits_per_pixel() as usize)
+            .map(|bits| bits / 8)
+    }
+
+    /// Returns the number of bytes per pixel if [`decode`] and [`encode`] support the format.
+    fn hub_bytes(self) -> Option<usize> {
+        match self {
+            PixelFormat::Yuv444 | PixelFormat::Yuyv | PixelFormat::Uyvy | PixelFormat::Yuv420p => {
+                None
+            }
+            _ => Some(self.bits_per_pixel() as usize / 8),
+        }
+    }
+}
+
+/// Intermediate pixel type of the dynamic conversion
+pub type Hub = Rgba<u16>;
+
+/// Number of pixels converted at once
+const CHUNK: usize = 64;
+
+/// Converts a frame from one pixel format to another.
+///
+/// **The YUV formats ([`PixelFormat::Yuv444`], [`PixelFormat::Yuyv`], [`PixelFormat::Uyvy`] and
+/// [`PixelFormat::Yuv420p`]) are not supported here.** They fail with [`Error::Unsupported`], use
+/// `ffimage_yuv::format::convert` instead, which handles all formats.
+///
+/// Fails if a buffer does not hold a tightly packed frame of `width` x `height` pixels in its
+/// format. Use [`convert_image`] for frames with padded rows.
+pub fn convert(
+    src_fmt: PixelFormat,
+    src: &[u8],
+    dst_fmt: PixelFormat,
+    dst: &mut [u8],
+    width: u32,
+    height: u32,
+) -> Result<(), Error> {
+    let (src_bytes, dst_bytes) = hub_bytes(src_fmt, dst_fmt)?;
+    check_len(src_fmt, src.len(), width, height)?;
+    check_len(dst_fmt, dst.len(), width, height)?;
+
+    let width = width as usize;
+    let height = height as usize;
+    convert_rows(
+        (src_fmt, src, width * src_bytes),
+        (dst_fmt, dst, width * dst_bytes),
+        width,
+        height,
+    )
+}
+
+/// Converts an image from one pixel format to another.
+///
+/// Like [`convert`], but honors the stride of both images. The YUV formats fail with
+/// [`Error::Unsupported`], use `ffimage_yuv::format::convert_image` for them. Fails with
+/// [`Error::InvalidDimensions`] if the images differ in size.
+///
+/// # Arguments
+///
+/// * `src` - Source image
+/// * `dst` - Destination image
+///
+/// # Example
+///
+/// ```
+/// use ffimage::{
+///     format::{convert_image, PixelFormat},
+///     image::{DynamicImageView, DynamicImageViewMut},
+/// };
+///
+/// // two rows of one pixel, padded to four bytes
+/// let bgr = [3u8, 2, 1, 0, 6, 5, 4, 0];
+/// let src = DynamicImageView::with_stride(&bgr, PixelFormat::Bgr24, 1, 2, 4).unwrap();
+/// let mut rgb = [0u8; 6];
+/// let mut dst = DynamicImageViewMut::new(&mut rgb, PixelFormat::Rgb24, 1, 2).unwrap();
+/// convert_image(&src, &mut dst).unwrap();
+///
+/// assert_eq!(rgb, [1, 2, 3, 4, 5, 6]);
+/// ```
+pub fn convert_image(src: &DynamicImageView, dst: &mut DynamicImageViewMut) -> Result<(), Error> {
+    hub_bytes(src.format(), dst.format())?;
+    if src.width() != dst.width() || src.height() != dst.height() {
+        return Err(Error::InvalidDimensions {
+            width: dst.width(),
+            height: dst.height(),
+        });
+    }
+
+    let (width, height) = (src.width() as usize, src.height() as usize);
+    let src_stride = src.stride();
+    let dst_stride = dst.stride();
+    let dst_fmt = dst.format();
+    convert_rows(
+        (src.format(), src.as_slice(), src_stride),
+        (dst_fmt, dst.as_mut_slice(), dst_stride),
+        width,
+        height,
+    )
+}
+
+/// Returns the number of bytes per pixel of both formats if [`convert`] supports them.
+fn hub_bytes(src_fmt: PixelFormat, dst_fmt: PixelFormat) -> Result<(usize, usize), Error> {
+    match (src_fmt.hub_bytes(), dst_fmt.hub_bytes()) {
+        (Some(src_bytes), Some(dst_bytes)) => Ok((src_bytes, dst_bytes)),
+        (None, _) => Err(Error::Unsupported(src_fmt)),
+        (_, None) => Err(Error::Unsupported(dst_fmt)),
+    }
+}
+
+/// Converts `height` rows of `width` pixels, each side given as format, bytes and stride.
+fn convert_rows(
+    (src_fmt, src, src_stride): (PixelFormat, &[u8], usize),
+    (dst_fmt, dst, dst_stride): (PixelFormat, &mut [u8], usize),
+    width: usize,
+    height: usize,
+) -> Result<(), Error> {
+    let src_bytes = src_fmt.bits_per_pixel() as usize / 8;
+    let dst_bytes = dst_fmt.bits_per_pixel() as usize / 8;
+
+    // tightly packed frames are converted as a single row
+    let (width, height) = if src_stride == width * src_bytes && dst_stride == width * dst_bytes {
+        (width * height, height.min(1))
+    } else {
+        (width, height)
+    };
+
+    for y in 0..height {
+        let src = &src[y * src_stride..][..width * src_bytes];
+        let dst = &mut dst[y * dst_stride..][..width * dst_bytes];
+        convert_row(src_fmt, src, dst_fmt, dst)?;
+    }
+
+    Ok(())
+}
+
+fn convert_row(
+    src_fmt: PixelFormat,
+    src: &[u8],
+    dst_fmt: PixelFormat,
+    dst: &mut [u8],
+) -> Result<(), Error> {
+    let src_bytes = src_fmt.bits_per_pixel() as usize / 8;
+    let dst_bytes = dst_fmt.bits_per_pixel() as usize / 8;
+
+    match (src_fmt, dst_fmt) {
+        _ if src_fmt == dst_fmt => dst.copy_from_slice(src),
+        (PixelFormat::Rgb24, PixelFormat::Bgr24) | (PixelFormat::Bgr24, PixelFormat::Rgb24) => {
+            simd::rgb_to_bgr(src, dst)
+        }
+        (PixelFormat::Rgb24, PixelFormat::Gray8) => simd::rgb_to_gray::<DefaultLuma>(src, dst),
+        _ => {
+            let mut hub = [Hub::default(); CHUNK];
+            for (src, dst) in src
+                .chunks(CHUNK * src_bytes)
+                .zip(dst.chunks_mut(CHUNK * dst_bytes))
+            {
+                let hub = &mut hub[..src.len() / src_bytes];
+                decode(src_fmt, src, hub)?;
+                encode(dst_fmt, hub, dst)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates that a buffer of `len` bytes holds a frame of the given format.
+pub fn check_len(fmt: PixelFormat, len: usize, width: u32, height: u32) -> Result<(), Error> {
+    let expected = fmt.frame_len(width, height)?;
+    if len != expected {
+        return Err(Error::LengthMismatch {
+            expected,
+            actual: len,
+        });
+    }
+
+    Ok(())
+}
+
+/// Sample stored in native byte order
+trait Raw: Copy + Default {
+    const SIZE: usize;
+
+    fn read(bytes: &[u8]) -> Self;
+    fn write(self, bytes: &mut [u8]);
+}
+
+impl Raw for u8 {
+    const SIZE: usize = 1;
+
+    fn read(bytes: &[u8]) -> Self {
+        bytes[0]
+    }
+
+    fn write(self, bytes: &mut [u8]) {
+        bytes[0] = self;
+    }
+}
+
+impl Raw for u16 {
+    const SIZE: usize = 2;
+
+    fn read(bytes: &[u8]) -> Self {
+        u16::from_ne_bytes([bytes[0], bytes[1]])
+    }
+
+    fn write(self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.to_ne_bytes())
+    }
+}
+
+fn read<T: Raw, P, const C: usize>(src: &[u8], dst: &mut [P], f: impl Fn([T; C]) -> P) {
+    for (src, dst) in src.chunks_exact(C * T::SIZE).zip(dst) {
+        let mut pix = [T::default(); C];
+        for (sample, bytes) in pix.iter_mut().zip(src.chunks_exact(T::SIZE)) {
+            *sample = T::read(bytes);
+        }
+        *dst = f(pix);
+    }
+}
+
+fn write<T: Raw, P: Copy, const C: usize>(src: &[P], dst: &mut [u8], f: impl Fn(P) -> [T; C]) {
+    for (src, dst) in src.iter().zip(dst.chunks_exact_mut(C * T::SIZE)) {
+        for (sample, bytes) in f(*src).iter().zip(dst.chunks_exact_mut(T::SIZE)) {
+            sample.write(bytes);
+        }
+    }
+}
+
+/// Rescales all samples of a pixel.
+fn depth<T: Sample, U: Sample, const N: usize>(pix: [T; N]) -> [U; N] {
+    pix.map(Sample::to_sample)
+}
+
+fn from_rgb8(rgb: Rgb<u8>) -> Hub {
+    Hub::from(Rgb::<u16>(depth(rgb.0)))
+}
+
+fn from_rgba8(rgba: Rgba<u8>) -> Hub {
+    Rgba(depth(rgba.0))
+}
+
+fn to_rgb8(hub: Hub) -> Rgb<u8> {
+    Rgb(depth(Rgb::<u16>::from(hub).0))
+}
+
+fn to_rgba8(hub: Hub) -> Rgba<u8> {
+    Rgba(depth(hub.0))
+}
+
+/// Decodes pixels of the given format into the intermediate pixel type.
+///
+/// Together with [`encode`], this allows other crates to convert their formats from and to all
+/// formats supported here. Fails if the format is not supported or if `src` does not hold
+/// exactly `dst.len()` pixels.
+pub fn decode(fmt: PixelFormat, src: &[u8], dst: &mut [Hub]) -> Result<(), Error> {
+    use PixelFormat as F;
+
+    let bytes = fmt.hub_bytes().ok_or(Error::Unsupported(fmt))?;
+    if src.len() != dst.len() * bytes {
+        return Err(Error::LengthMismatch {
+            expected: dst.len() * bytes,
+            actual: src.len(),
+        });
+    }
+
+    match fmt {
+        F::Gray8 => read::<u8, _, 1>(src, dst, |s| Hub::from(Gray::<u16>(depth(s)))),
+        F::Gray16 => read::<u16, _, 1>(src, dst, |s| Hub::from(Gray(s))),
+        F::GrayAlpha8 => read::<u8, _, 2>(src, dst, |s| Hub::from(GrayAlpha::<u16>(depth(s)))),
+        F::GrayAlpha16 => read::<u16, _, 2>(src, dst, |s| Hub::from(GrayAlpha(s))),
+        F::Rgb24 => read::<u8, _, 3>(src, dst, |s| from_rgb8(Rgb(s))),
+        F::Bgr24 => read::<u8, _, 3>(src, dst, |s| Hub::from(Bgr::<u16>::from(depth(s)))),
+        F::Rgb48 => read::<u16, _, 3>(src, dst, |s| Hub::from(Rgb::<u16>(s))),
+        F::Bgr48 => read::<u16, _, 3>(src, dst, |s| Hub::from(Bgr::<u16>::from(s))),
+        F::Rgba32 => read::<u8, _, 4>(src, dst, |s| from_rgba8(Rgba(s))),
+        F::Bgra32 => read::<u8, _, 4>(src, dst, |s| Hub::from(Bgra::<u16>::from(depth(s)))),
+        F::Argb32 => read::<u8, _, 4>(src, dst, |s| Hub::from(Argb::<u16>::from(depth(s)))),
+        F::Abgr32 => read::<u8, _, 4>(src, dst, |s| Hub::from(Abgr::<u16>::from(depth(s)))),
+        F::Rgba64 => read::<u16, _, 4>(src, dst, Rgba),
+        F::Bgra64 => read::<u16, _, 4>(src, dst, |s| Hub::from(Bgra::<u16>::from(s))),
+        F::Rgb565 => read::<u8, _, 2>(src, dst, |s| from_rgb8(Rgb::from(<Rgb565>::from(s)))),
+        F::Bgr565 => read::<u8, _, 2>(src, dst, |s| from_rgb8(Rgb::from(<Bgr565>::from(s)))),
+        F::Rgb555 => read::<u8, _, 2>(src, dst, |s| from_rgb8(Rgb::from(<Rgb555>::from(s)))),
+        F::Argb1555 => read::<u8, _, 2>(src, dst, |s| from_rgba8(Rgba::from(<Argb1555>::from(s)))),
+        F::Rgb10a2 => read::<u8, _, 4>(src, dst, |s| Hub::from(Rgb10a2::from(s))),
+        F::Bgr10a2 => read::<u8, _, 4>(src, dst, |s| Hub::from(Bgr10a2::from(s))),
+        F::X2Rgb10 => read::<u8, _, 4>(src, dst, |s| Hub::from(Rgb::<u16>::from(X2Rgb10::from(s)))),
+        F::X2Bgr10 => read::<u8, _, 4>(src, dst, |s| Hub::from(Rgb::<u16>::from(X2Bgr10::from(s)))),
+        F::Hsv24 => read::<u8, _, 3>(src, dst, |s| from_rgb8(Rgb::from(Hsv::<u8>::from(s)))),
+        F::Hsl24 => read::<u8, _, 3>(src, dst, |s| from_rgb8(Rgb::from(Hsl::<u8>::from(s)))),
+        F::Yuv444 | F::Yuyv | F::Uyvy | F::Yuv420p => unreachable!(),
+    }
+
+    Ok(())
+}
+
+/// Encodes pixels of the intermediate pixel type into the given format.
+///
+/// Fails if the format is not supported or if `dst` does not hold exactly `src.len()` pixels.
+pub fn encode(fmt: PixelFormat, src: &[Hub], dst: &mut [u8]) -> Result<(), Error> {
+    use PixelFormat as F;
+
+    let bytes = fmt.hub_bytes().ok_or(Error::Unsupported(fmt))?;
+    if dst.len() != src.len() * bytes {
+        return Err(Error::LengthMismatch {
+            expected: src.len() * bytes,
+            actual: dst.len(),
+        });
+    }
+
+    match fmt {
+        F::Gray8 => write::<u8, _, 1>(src, dst, |p| Gray::<u8>::from(to_rgb8(p)).0),
+        F::Gray16 => write::<u16, _, 1>(src, dst, |p| Gray::<u16>::from(p).0),
+        F::GrayAlpha8 => write::<u8, _, 2>(src, dst, |p| GrayAlpha::<u8>::from(to_rgba8(p)).0),
+        F::GrayAlpha16 => write::<u16, _, 2>(src, dst, |p| GrayAlpha::<u16>::from(p).0),
+        F::Rgb24 => write::<u8, _, 3>(src, dst, |p| to_rgb8(p).0),
+        F::Bgr24 => write::<u8, _, 3>(src, dst, |p| depth(Bgr::<u16>::from(p).0)),
+        F::Rgb48 => write::<u16, _, 3>(src, dst, |p| Rgb::<u16>::from(p).0),
+        F::Bgr48 => write::<u16, _, 3>(src, dst, |p| Bgr::<u16>::from(p).0),
+        F::Rgba32 => write::<u8, _, 4>(src, dst, |p| to_rgba8(p).0),
+        F::Bgra32 => write::<u8, _, 4>(src, dst, |p| depth(Bgra::<u16>::from(p).0)),
+        F::Argb32 => write::<u8, _, 4>(src, dst, |p| depth(Argb::<u16>::from(p).0)),
+        F::Abgr32 => write::<u8, _, 4>(src, dst, |p| depth(Abgr::<u16>::from(p).0)),
+        F::Rgba64 => write::<u16, _, 4>(src, dst, |p| p.0),
+        F::Bgra64 => write::<u16, _, 4>(src, dst, |p| Bgra::<u16>::from(p).0),
+        F::Rgb565 => write::<u8, _, 2>(src, dst, |p| <Rgb565>::from(to_rgb8(p)).0),
+        F::Bgr565 => write::<u8, _, 2>(src, dst, |p| <Bgr565>::from(to_rgb8(p)).0),
+        F::Rgb555 => write::<u8, _, 2>(src, dst, |p| <Rgb555>::from(to_rgb8(p)).0),
+        F::Argb1555 => write::<u8, _, 2>(src, dst, |p| <Argb1555>::from(to_rgba8(p)).0),
+        F::Rgb10a2 => write::<u8, _, 4>(src, dst, |p| Rgb10a2::from(p).0),
+        F::Bgr10a2 => write::<u8, _, 4>(src, dst, |p| Bgr10a2::from(p).0),
+        F::X2Rgb10 => write::<u8, _, 4>(src, dst, |p| X2Rgb10::from(Rgb::<u16>::from(p)).0),
+        F::X2Bgr10 => write::<u8, _, 4>(src, dst, |p| X2Bgr10::from(Rgb::<u16>::from(p)).0),
+        F::Hsv24 => write::<u8, _, 3>(src, dst, |p| Hsv::<u8>::from(to_rgb8(p)).0),
+        F::Hsl24 => write::<u8, _, 3>(src, dst, |p| Hsl::<u8>::from(to_rgb8(p)).0),
+        F::Yuv444 | F::Yuyv | F::Uyvy | F::Yuv420p => unreachable!(),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cast, iter::*};
+
+    #[test]
+    fn frame_len() {
+        assert_eq!(PixelFormat::Rgb24.frame_len(4, 3), Ok(36));
+        assert_eq!(PixelFormat::Rgba64.frame_len(4, 3), Ok(96));
+        assert_eq!(PixelFormat::Yuyv.frame_len(4, 3), Ok(24));
+        assert_eq!(PixelFormat::Yuv420p.frame_len(4, 2), Ok(12));
+        assert_eq!(
+            PixelFormat::Yuv420p.frame_len(4, 3),
+            Err(Error::InvalidDimensions {
+                width: 4,
+                height: 3
+            })
+        );
+    }
+
+    #[test]
+    fn typed() {
+        // compares against the typed paths
+        let rgb: [u8; 3 * 70] = core::array::from_fn(|i| (i * 37) as u8);
+
+        let mut dst = [0u8; 4 * 70];
+        convert(
+            PixelFormat::Rgb24,
+            &rgb,
+            PixelFormat::Abgr32,
+            &mut dst,
+            7,
+            10,
+        )
+        .unwrap();
+        for (dst, rgb) in cast::as_pixels::<Abgr<u8>>(&dst)
+            .unwrap()
+            .iter()
+            .zip(rgb.iter().copied().pixels::<Rgb<u8>>())
+        {
+            assert_eq!(*dst, Abgr::<u8>::from(Rgba::<u8>::from(rgb)));
+        }
+
+        let mut dst = [0u8; 2 * 70];
+        convert(
+            PixelFormat::Rgb24,
+            &rgb,
+            PixelFormat::Rgb565,
+            &mut dst,
+            70,
+            1,
+        )
+        .unwrap();
+        for (dst, rgb) in dst
+            .iter()
+            .copied()
+            .pixels::<Rgb565>()
+            .zip(rgb.iter().copied().pixels::<Rgb<u8>>())
+        {
+            assert_eq!(dst, Rgb565::from(rgb));
+        }
+
+        let mut dst = [0u8; 3 * 70];
+        convert(
+            PixelFormat::Rgb24,
+            &rgb,
+            PixelFormat::Hsv24,
+            &mut dst,
+            70,
+            1,
+        )
+        .unwrap();
+        for (dst, rgb) in dst
+            .iter()
+            .copied()
+            .pixels::<Hsv<u8>>()
+            .zip(rgb.iter().copied().pixels::<Rgb<u8>>())
+        {
+            assert_eq!(dst, Hsv::from(rgb));
+        }
+    }
+
+    #[test]
+    fn gray8() {
+        // every 8 bit RGB layout yields the same gray value as the typed path
+        for &rgb in &[[3u8, 80, 189], [255, 0, 0], [12, 200, 34], [90, 91, 92]] {
+            let expected = Gray::<u8>::from(Rgb::<u8>(rgb))[0];
+
+            for &(fmt, pix) in &[
+                (PixelFormat::Rgb24, &[rgb[0], rgb[1], rgb[2]][..]),
+                (PixelFormat::Bgr24, &[rgb[2], rgb[1], rgb[0]][..]),
+                (PixelFormat::Rgba32, &[rgb[0], rgb[1], rgb[2], 255][..]),
+                (PixelFormat::Bgra32, &[rgb[2], rgb[1], rgb[0], 255][..]),
+                (PixelFormat::Abgr32, &[255, rgb[2], rgb[1], rgb[0]][..]),
+            ] {
+                let mut gray = [0u8; 1];
+                convert(fmt, pix, PixelFormat::Gray8, &mut gray, 1, 1).unwrap();
+                assert_eq!(gray[0], expected, "{:?} {:?}", fmt, rgb);
+            }
+        }
+    }
+
+    #[test]
+    fn depth_independent() {
+        // 8 bit targets yield the same result no matter the depth of the source
+        let rgb: [u8; 3 * 64] = core::array::from_fn(|i| (i * 37) as u8);
+        let mut rgb48 = [0u8; 6 * 64];
+        convert(
+            PixelFormat::Rgb24,
+            &rgb,
+            PixelFormat::Rgb48,
+            &mut rgb48,
+            64,
+            1,
+        )
+        .unwrap();
+
+        for &fmt in &[
+            PixelFormat::Gray8,
+            PixelFormat::GrayAlpha8,
+            PixelFormat::Hsv24,
+            PixelFormat::Rgb565,
+        ] {
+            let mut a = [0u8; 3 * 64];
+            let mut b = [0u8; 3 * 64];
+            let len = fmt.frame_len(64, 1).unwrap();
+            convert(PixelFormat::Rgb24, &rgb, fmt, &mut a[..len], 64, 1).unwrap();
+            convert(PixelFormat::Rgb48, &rgb48, fmt, &mut b[..len], 64, 1).unwrap();
+            assert_eq!(a, b, "{:?}", fmt);
+        }
+
+        let gray: [u8; 256] = core::array::from_fn(|i| i as u8);
+        let mut gray16 = [0u8; 2 * 256];
+        let mut out = [0u8; 256];
+        convert(
+            PixelFormat::Gray8,
+            &gray,
+            PixelFormat::Gray16,
+            &mut gray16,
+            256,
+            1,
+        )
+        .unwrap();
+        convert(
+            PixelFormat::Gray16,
+            &gray16,
+            PixelFormat::Gray8,
+            &mut out,
+            256,
+            1,
+        )
+        .unwrap();
+        assert_eq!(out, gray);
+    }
+
+    #[test]
+    fn convert_image() {
+        // 2x2 BGRA image with four bytes of padding per row
+        let bgra = [
+            3u8, 2, 1, 255, 6, 5, 4, 255, 0, 0, 0, 0, 9, 8, 7, 255, 12, 11, 10, 255,
+        ];
+        let src = DynamicImageView::with_stride(&bgra, PixelFormat::Bgra32, 2, 2, 12).unwrap();
+        // one byte of padding per row
+        let mut rgb = [0u8; 13];
+        let mut dst =
+            DynamicImageViewMut::with_stride(&mut rgb, PixelFormat::Rgb24, 2, 2, 7).unwrap();
+
+        super::convert_image(&src, &mut dst).unwrap();
+        assert_eq!(rgb, [1, 2, 3, 4, 5, 6, 0, 7, 8, 9, 10, 11, 12]);
+
+        let mut gray = [0u8; 2];
+        let mut dst = DynamicImageViewMut::new(&mut gray, PixelFormat::Gray8, 2, 1).unwrap();
+        assert_eq!(
+            super::convert_image(&src, &mut dst),
+            Err(Error::InvalidDimensions {
+                width: 2,
+                height: 1
+            })
+        );
+
+        let mut yuyv = [0u8; 8];
+        let mut dst = DynamicImageViewMut::new(&mut yuyv, PixelFormat::Yuyv, 2, 2).unwrap();
+        assert_eq!(
+            super::convert_image(&src, &mut dst),
+            Err(Error::Unsupported(PixelFormat::Yuyv))
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let rgba: [u8; 4 * 100] = core::array::from_fn(|i| (i * 13) as u8);
+
+        // formats which hold 8 bit RGBA losslessly
+        for &fmt in &[
+            PixelFormat::Rgba32,
+            PixelFormat::Bgra32,
+            PixelFormat::Argb32,
+            PixelFormat::Abgr32,
+            PixelFormat::Rgba64,
+            PixelFormat::Bgra64,
+        ] {
+            let mut tmp = [0u8; 8 * 100];
+            let tmp = &mut tmp[..fmt.frame_len(10, 10).unwrap()];
+            let mut out = [0u8; 4 * 100];
+            convert(PixelFormat::Rgba32, &rgba, fmt, tmp, 10, 10).unwrap();
+            convert(fmt, tmp, PixelFormat::Rgba32, &mut out, 10, 10).unwrap();
+            assert_eq!(out, rgba, "{:?}", fmt);
+        }
+    }
+
+    #[test]
+    fn all() {
+        let src = [0x55u8; 64 * 4];
+
+        for &src_fmt in PixelFormat::ALL {
+            for &dst_fmt in PixelFormat::ALL {
+                let mut dst = [0u8; 64 * 8];
+                let src = &src[..src_fmt.frame_len(4, 4).unwrap()];
+                let dst = &mut dst[..dst_fmt.frame_len(4, 4).unwrap()];
+
+                let res = convert(src_fmt, src, dst_fmt, dst, 4, 4);
+                match (src_fmt.hub_bytes(), dst_fmt.hub_bytes()) {
+                    (Some(_), Some(_)) => assert_eq!(res, Ok(())),
+                    (None, _) => assert_eq!(res, Err(Error::Unsupported(src_fmt))),
+                    (_, None) => assert_eq!(res, Err(Error::Unsupported(dst_fmt))),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn length_mismatch() {
+        let mut dst = [0u8; 12];
+        assert_eq!(
+            convert(
+                PixelFormat::Rgb24,
+                &[0; 9],
+                PixelFormat::Gray8,
+                &mut dst,
+                2,
+                2
+            ),
+            Err(Error::LengthMismatch {
+                expected: 12,
+                actual: 9
+            })
+        );
+        assert_eq!(
+            convert(
+                PixelFormat::Rgb24,
+                &[0; 12],
+                PixelFormat::Gray8,
+                &mut dst,
+                2,
+                2
+            ),
+            Err(Error::LengthMismatch {
+                expected: 4,
+                actual: 12
+            })
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use alloc::{vec, vec::Vec};
+use core::{marker::PhantomData, mem};
+
+use crate::{
+    image::view::{layout, row_len, ImageView, ImageViewMut},
+    Pixel,
+};
+
+/// Image which owns its (strided) memory.
+///
+/// Use [`ImageBuffer::as_view`] and [`ImageBuffer::as_view_mut`] to access the pixels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageBuffer<P, T = u8> {
+    _marker: PhantomData<P>,
+    buf: Vec<T>,
+    width: u32,
+    height: u32,
+    stride: usize,
+}
+
+impl<P, T> ImageBuffer<P, T>
+where
+    P: Pixel,
+    T: Copy + Default,
+{
+    /// Returns a new, tightly packed image with all samples set to their default value.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    pub fn new(width: u32, height: u32) -> Option<Self> {
+        let row_len = row_len::<P>(width)?;
+
+        Some(ImageBuffer {
+            _marker: PhantomData,
+            buf: vec![T::default(); row_len * height as usize],
+            width,
+            height,
+            stride: row_len * mem::size_of::<T>(),
+        })
+    }
+}
+
+impl<P, T> ImageBuffer<P, T>
+where
+    P: Pixel,
+    T: Copy,
+{
+    /// Returns an image which takes ownership of tightly packed memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Image memory
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    pub fn from_raw(buf: Vec<T>, width: u32, height: u32) -> Option<Self> {
+        let stride = row_len::<P>(width)? * mem::size_of::<T>();
+        Self::from_raw_with_stride(buf, width, height, stride)
+    }
+
+    /// Returns an image which takes ownership of memory with padded rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Image memory
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    /// * `stride` - Length of a row in bytes, including padding
+    pub fn from_raw_with_stride(
+        buf: Vec<T>,
+        width: u32,
+        height: u32,
+        stride: usize,
+    ) -> Option<Self> {
+        layout::<P, T>(buf.len(), width, height, stride)?;
+
+        Some(ImageBuffer {
+            _marker: PhantomData,
+            buf,
+            width,
+            height,
+            stride,
+        })
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the length of a row in bytes, including padding.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns a read-only view of the image.
+    pub fn as_view(&self) -> ImageView<'_, P, T> {
+        ImageView::with_stride(&self.buf, self.width, self.height, self.stride)
+            .expect("layout was validated on construction")
+    }
+
+    /// Returns a mutable view of the image.
+    pub fn as_view_mut(&mut self) -> ImageViewMut<'_, P, T> {
+        ImageViewMut::with_stride(&mut self.buf, self.width, self.height, self.stride)
+            .expect("layout was validated on construction")
+    }
+
+    /// Consumes the image and returns its memory.
+    pub fn into_raw(self) -> Vec<T> {
+        self.buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::{Bgr, Rgb},
+        iter::{BytesExt, ColorConvertExt, PixelsExt},
+    };
+
+    #[test]
+    fn new() {
+        let img = ImageBuffer::<Rgb<u16>, u16>::new(4, 2).unwrap();
+
+        assert_eq!(img.stride(), 4 * 3 * 2);
+        assert_eq!(img.into_raw().len(), 4 * 2 * 3);
+    }
+
+    #[test]
+    fn convert() {
+        let rgb = ImageBuffer::<Rgb<u8>>::from_raw_with_stride(
+            vec![1, 2, 3, 4, 5, 6, 0, 0, 7, 8, 9, 10, 11, 12],
+            2,
+            2,
+            8,
+        )
+        .unwrap();
+        let mut bgr = ImageBuffer::<Bgr<u8>>::new(2, 2).unwrap();
+
+        rgb.as_view()
+            .samples()
+            .pixels::<Rgb<u8>>()
+            .colorconvert::<Bgr<u8>>()
+            .bytes()
+            .write(bgr.as_view_mut().samples_mut());
+        assert_eq!(bgr.into_raw(), [3, 2, 1, 6, 5, 4, 9, 8, 7, 12, 11, 10]);
+    }
+}
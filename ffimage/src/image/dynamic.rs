@@ -0,0 +1,452 @@
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::mem;
+
+use crate::format::PixelFormat;
+
+/// Returns the number of bytes of a single image row which are occupied by pixels.
+///
+/// Planar formats are tightly packed, their rows are those of the first plane.
+fn row_len(format: PixelFormat, width: u32) -> Option<usize> {
+    match format.row_len(width) {
+        Some(row_len) => Some(row_len),
+        None if format.planes() > 1 => Some(width as usize),
+        None => None,
+    }
+}
+
+/// Validates the image geometry and returns the row length in bytes.
+fn layout(
+    format: PixelFormat,
+    len: usize,
+    width: u32,
+    height: u32,
+    stride: usize,
+) -> Option<usize> {
+    let row_len = row_len(format, width)?;
+
+    let min_len = if format.planes() > 1 {
+        // the planes follow each other, so their rows cannot be padded
+        if stride != row_len {
+            return None;
+        }
+        format.frame_len(width, height).ok()?
+    } else {
+        if stride < row_len {
+            return None;
+        }
+        // the last row does not need to be padded
+        match height {
+            0 => 0,
+            _ => (height as usize - 1)
+                .checked_mul(stride)?
+                .checked_add(row_len)?,
+        }
+    };
+    if len < min_len {
+        return None;
+    }
+
+    Some(row_len)
+}
+
+/// Read-only view into strided image memory whose pixel format is only known at runtime.
+///
+/// This is the runtime-typed counterpart of [`crate::image::ImageView`]. Rows of single plane
+/// formats may be padded, planar formats (i.e. [`PixelFormat::Yuv420p`]) must be tightly
+/// packed. Use [`crate::format::convert_image`] to convert the pixels.
+#[derive(Debug, Clone, Copy)]
+pub struct DynamicImageView<'a> {
+    buf: &'a [u8],
+    format: PixelFormat,
+    width: u32,
+    height: u32,
+    row_len: usize,
+    stride: usize,
+}
+
+impl<'a> DynamicImageView<'a> {
+    /// Returns a view into tightly packed image memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Image memory
+    /// * `format` - Pixel format
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    pub fn new(buf: &'a [u8], format: PixelFormat, width: u32, height: u32) -> Option<Self> {
+        let stride = row_len(format, width)?;
+        Self::with_stride(buf, format, width, height, stride)
+    }
+
+    /// Returns a view into image memory with padded rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Image memory
+    /// * `format` - Pixel format
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    /// * `stride` - Length of a row in bytes, including padding
+    pub fn with_stride(
+        buf: &'a [u8],
+        format: PixelFormat,
+        width: u32,
+        height: u32,
+        stride: usize,
+    ) -> Option<Self> {
+        let row_len = layout(format, buf.len(), width, height, stride)?;
+
+        Some(DynamicImageView {
+            buf,
+            format,
+            width,
+            height,
+            row_len,
+            stride,
+        })
+    }
+
+    /// Returns the pixel format.
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the length of a row in bytes, including padding.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the underlying memory, including padding.
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.buf
+    }
+
+    /// Returns the bytes of a single row, excluding padding.
+    ///
+    /// Rows of planar formats only cover the first plane.
+    pub fn row(&self, i: u32) -> Option<&'a [u8]> {
+        if i >= self.height {
+            return None;
+        }
+
+        let offset = i as usize * self.stride;
+        Some(&self.buf[offset..offset + self.row_len])
+    }
+
+    /// Returns an iterator over all rows, excluding padding.
+    ///
+    /// Rows of planar formats only cover the first plane.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        let (buf, row_len, stride) = (self.buf, self.row_len, self.stride);
+        (0..self.height as usize).map(move |y| &buf[y * stride..y * stride + row_len])
+    }
+}
+
+/// Mutable view into strided image memory whose pixel format is only known at runtime.
+///
+/// This is the mutable counterpart of [`DynamicImageView`].
+#[derive(Debug)]
+pub struct DynamicImageViewMut<'a> {
+    buf: &'a mut [u8],
+    format: PixelFormat,
+    width: u32,
+    height: u32,
+    row_len: usize,
+    stride: usize,
+}
+
+impl<'a> DynamicImageViewMut<'a> {
+    /// Returns a mutable view into tightly packed image memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Image memory
+    /// * `format` - Pixel format
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    pub fn new(buf: &'a mut [u8], format: PixelFormat, width: u32, height: u32) -> Option<Self> {
+        let stride = row_len(format, width)?;
+        Self::with_stride(buf, format, width, height, stride)
+    }
+
+    /// Returns a mutable view into image memory with padded rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Image memory
+    /// * `format` - Pixel format
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    /// * `stride` - Length of a row in bytes, including padding
+    pub fn with_stride(
+        buf: &'a mut [u8],
+        format: PixelFormat,
+        width: u32,
+        height: u32,
+        stride: usize,
+    ) -> Option<Self> {
+        let row_len = layout(format, buf.len(), width, height, stride)?;
+
+        Some(DynamicImageViewMut {
+            buf,
+            format,
+            width,
+            height,
+            row_len,
+            stride,
+        })
+    }
+
+    /// Returns the pixel format.
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the length of a row in bytes, including padding.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns a read-only view of the same memory.
+    pub fn as_view(&self) -> DynamicImageView<'_> {
+        DynamicImageView {
+            buf: self.buf,
+            format: self.format,
+            width: self.width,
+            height: self.height,
+            row_len: self.row_len,
+            stride: self.stride,
+        }
+    }
+
+    /// Returns the underlying memory, including padding.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.buf
+    }
+
+    /// Returns the bytes of a single row, excluding padding.
+    ///
+    /// Rows of planar formats only cover the first plane.
+    pub fn row_mut(&mut self, i: u32) -> Option<&mut [u8]> {
+        if i >= self.height {
+            return None;
+        }
+
+        let offset = i as usize * self.stride;
+        Some(&mut self.buf[offset..offset + self.row_len])
+    }
+
+    /// Returns an iterator over all rows, excluding padding.
+    ///
+    /// Rows of planar formats only cover the first plane.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [u8]> {
+        let (row_len, stride, height) = (self.row_len, self.stride, self.height as usize);
+        let mut rest = &mut *self.buf;
+        (0..height).map(move |y| {
+            let (row, tail) = mem::take(&mut rest).split_at_mut(row_len);
+            // the last row does not need to be padded
+            if y + 1 < height {
+                rest = &mut tail[stride - row_len..];
+            }
+            row
+        })
+    }
+}
+
+/// Image which owns its (strided) memory and whose pixel format is only known at runtime.
+///
+/// Use [`DynamicImageBuffer::as_view`] and [`DynamicImageBuffer::as_view_mut`] to access the
+/// pixels.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynamicImageBuffer {
+    buf: Vec<u8>,
+    format: PixelFormat,
+    width: u32,
+    height: u32,
+    stride: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl DynamicImageBuffer {
+    /// Returns a new, tightly packed image with all bytes set to zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - Pixel format
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    pub fn new(format: PixelFormat, width: u32, height: u32) -> Option<Self> {
+        let stride = row_len(format, width)?;
+        let len = if format.planes() > 1 {
+            format.frame_len(width, height).ok()?
+        } else {
+            stride.checked_mul(height as usize)?
+        };
+
+        Some(DynamicImageBuffer {
+            buf: vec![0; len],
+            format,
+            width,
+            height,
+            stride,
+        })
+    }
+
+    /// Returns an image which takes ownership of tightly packed memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Image memory
+    /// * `format` - Pixel format
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    pub fn from_raw(buf: Vec<u8>, format: PixelFormat, width: u32, height: u32) -> Option<Self> {
+        let stride = row_len(format, width)?;
+        Self::from_raw_with_stride(buf, format, width, height, stride)
+    }
+
+    /// Returns an image which takes ownership of memory with padded rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Image memory
+    /// * `format` - Pixel format
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    /// * `stride` - Length of a row in bytes, including padding
+    pub fn from_raw_with_stride(
+        buf: Vec<u8>,
+        format: PixelFormat,
+        width: u32,
+        height: u32,
+        stride: usize,
+    ) -> Option<Self> {
+        layout(format, buf.len(), width, height, stride)?;
+
+        Some(DynamicImageBuffer {
+            buf,
+            format,
+            width,
+            height,
+            stride,
+        })
+    }
+
+    /// Returns the pixel format.
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the length of a row in bytes, including padding.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns a read-only view of the image.
+    pub fn as_view(&self) -> DynamicImageView<'_> {
+        DynamicImageView::with_stride(&self.buf, self.format, self.width, self.height, self.stride)
+            .expect("layout was validated on construction")
+    }
+
+    /// Returns a mutable view of the image.
+    pub fn as_view_mut(&mut self) -> DynamicImageViewMut<'_> {
+        DynamicImageViewMut::with_stride(
+            &mut self.buf,
+            self.format,
+            self.width,
+            self.height,
+            self.stride,
+        )
+        .expect("layout was validated on construction")
+    }
+
+    /// Consumes the image and returns its memory.
+    pub fn into_raw(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout() {
+        let buf = [0u8; 2 * 8];
+
+        // tightly packed
+        assert!(DynamicImageView::new(&buf[..12], PixelFormat::Rgb24, 2, 2).is_some());
+        assert!(DynamicImageView::new(&buf[..11], PixelFormat::Rgb24, 2, 2).is_none());
+        // padded rows, last row without padding
+        assert!(DynamicImageView::with_stride(&buf[..14], PixelFormat::Rgb24, 2, 2, 8).is_some());
+        assert!(DynamicImageView::with_stride(&buf, PixelFormat::Rgb24, 2, 2, 5).is_none());
+        assert!(
+            DynamicImageView::with_stride(&buf, PixelFormat::Rgb24, 2, 2, usize::MAX).is_none()
+        );
+        // macropixels cannot be split
+        assert!(DynamicImageView::new(&buf, PixelFormat::Yuyv, 3, 2).is_none());
+        // planar formats are tightly packed
+        assert!(DynamicImageView::new(&buf[..6], PixelFormat::Yuv420p, 2, 2).is_some());
+        assert!(DynamicImageView::with_stride(&buf, PixelFormat::Yuv420p, 2, 2, 4).is_none());
+        assert!(DynamicImageView::new(&buf, PixelFormat::Yuv420p, 2, 3).is_none());
+    }
+
+    #[test]
+    fn rows() {
+        let mut buf = [1, 2, 3, 0, 4, 5, 6];
+        let mut view =
+            DynamicImageViewMut::with_stride(&mut buf, PixelFormat::Rgb24, 1, 2, 4).unwrap();
+
+        for row in view.rows_mut() {
+            row.reverse();
+        }
+        assert_eq!(view.as_view().row(1), Some(&[6, 5, 4][..]));
+        assert_eq!(buf, [3, 2, 1, 0, 6, 5, 4]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn buffer() {
+        let img = DynamicImageBuffer::new(PixelFormat::Yuv420p, 4, 2).unwrap();
+        assert_eq!(img.stride(), 4);
+        assert_eq!(img.into_raw().len(), 12);
+
+        let img = DynamicImageBuffer::new(PixelFormat::Rgb565, 3, 2).unwrap();
+        assert_eq!(img.as_view().rows().count(), 2);
+        assert_eq!(img.into_raw().len(), 12);
+
+        assert!(DynamicImageBuffer::from_raw(vec![0; 5], PixelFormat::Gray16, 3, 1).is_none());
+    }
+}
@@ -0,0 +1,10 @@
+pub mod view;
+pub use view::*;
+
+pub mod dynamic;
+pub use dynamic::*;
+
+#[cfg(feature = "alloc")]
+pub mod buffer;
+#[cfg(feature = "alloc")]
+pub use buffer::*;
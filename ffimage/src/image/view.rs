@@ -0,0 +1,529 @@
+use core::{marker::PhantomData, mem};
+
+use crate::Pixel;
+
+/// Returns the number of samples of a single image row which are occupied by pixels.
+///
+/// Macropixels (e.g. YUYV) cover more than one image pixel, so the width has to be a multiple of
+/// the number of subpixels.
+pub(crate) fn row_len<P: Pixel>(width: u32) -> Option<usize> {
+    let subpixels = P::SUBPIXELS as usize;
+    if width as usize % subpixels != 0 {
+        return None;
+    }
+
+    Some(width as usize / subpixels * P::CHANNELS as usize)
+}
+
+/// Validates the image geometry and returns the row length and the stride in samples.
+pub(crate) fn layout<P: Pixel, T>(
+    len: usize,
+    width: u32,
+    height: u32,
+    stride: usize,
+) -> Option<(usize, usize)> {
+    let row_len = row_len::<P>(width)?;
+
+    // the stride is given in bytes, but must describe whole samples
+    let sample_size = mem::size_of::<T>().max(1);
+    if stride % sample_size != 0 {
+        return None;
+    }
+    let stride = stride / sample_size;
+    if stride < row_len {
+        return None;
+    }
+
+    // the last row does not need to be padded
+    if height > 0 {
+        let min_len = (height as usize - 1)
+            .checked_mul(stride)?
+            .checked_add(row_len)?;
+        if len < min_len {
+            return None;
+        }
+    }
+
+    Some((row_len, stride))
+}
+
+/// Returns the sample range of the given image memory which is covered by a rectangular region.
+fn region<P: Pixel>(
+    width: u32,
+    height: u32,
+    stride: usize,
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+) -> Option<(usize, usize)> {
+    // macropixels cannot be split
+    row_len::<P>(x)?;
+    let row_len = row_len::<P>(w)?;
+
+    if x.checked_add(w)? > width || y.checked_add(h)? > height {
+        return None;
+    }
+
+    // regions without rows cover no memory, their start may lie past the unpadded last row
+    if h == 0 {
+        return Some((0, 0));
+    }
+
+    let start = y as usize * stride + x as usize / P::SUBPIXELS as usize * P::CHANNELS as usize;
+    let end = start + (h as usize - 1) * stride + row_len;
+
+    Some((start, end))
+}
+
+/// Read-only view into strided image memory.
+///
+/// The view does not own the memory, it merely describes its layout. Rows may be padded, i.e.
+/// the stride (in bytes) can be larger than the number of bytes occupied by the pixels of a row.
+/// This is usually the case for buffers provided by camera drivers (see `bytesperline` in V4L2).
+///
+/// Pixels are accessed through the existing iterator adapters:
+///
+/// ```
+/// use ffimage::color::{Gray, Rgb};
+/// use ffimage::image::ImageView;
+/// use ffimage::iter::{BytesExt, ColorConvertExt, PixelsExt};
+///
+/// // 2x2 RGB image with one byte of padding per row
+/// let rgb = [10; 2 * 7];
+/// let mut gray = [0; 2 * 2];
+///
+/// let view = ImageView::<Rgb<u8>>::with_stride(&rgb, 2, 2, 7).unwrap();
+/// view.samples()
+///     .pixels::<Rgb<u8>>()
+///     .colorconvert::<Gray<u8>>()
+///     .bytes()
+///     .write(&mut gray);
+/// ```
+#[derive(Debug)]
+pub struct ImageView<'a, P, T = u8> {
+    _marker: PhantomData<P>,
+    buf: &'a [T],
+    width: u32,
+    height: u32,
+    row_len: usize,
+    stride: usize,
+}
+
+impl<'a, P, T> Clone for ImageView<'a, P, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, P, T> Copy for ImageView<'a, P, T> {}
+
+impl<'a, P, T> ImageView<'a, P, T>
+where
+    P: Pixel,
+    T: Copy,
+{
+    /// Returns a view into tightly packed image memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Image memory
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    pub fn new(buf: &'a [T], width: u32, height: u32) -> Option<Self> {
+        let stride = row_len::<P>(width)? * mem::size_of::<T>();
+        Self::with_stride(buf, width, height, stride)
+    }
+
+    /// Returns a view into image memory with padded rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Image memory
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    /// * `stride` - Length of a row in bytes, including padding
+    pub fn with_stride(buf: &'a [T], width: u32, height: u32, stride: usize) -> Option<Self> {
+        let (row_len, stride) = layout::<P, T>(buf.len(), width, height, stride)?;
+
+        Some(ImageView {
+            _marker: PhantomData,
+            buf,
+            width,
+            height,
+            row_len,
+            stride,
+        })
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the length of a row in bytes, including padding.
+    pub fn stride(&self) -> usize {
+        self.stride * mem::size_of::<T>()
+    }
+
+    /// Returns the underlying memory, including padding.
+    pub fn as_slice(&self) -> &'a [T] {
+        self.buf
+    }
+
+    /// Returns the samples of a single row, excluding padding.
+    pub fn row(&self, i: u32) -> Option<&'a [T]> {
+        if i >= self.height {
+            return None;
+        }
+
+        let offset = i as usize * self.stride;
+        Some(&self.buf[offset..offset + self.row_len])
+    }
+
+    /// Returns an iterator over all rows, excluding padding.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + 'a {
+        let (buf, row_len, stride) = (self.buf, self.row_len, self.stride);
+        (0..self.height as usize).map(move |y| &buf[y * stride..y * stride + row_len])
+    }
+
+    /// Returns a view into a rectangular region of the image.
+    ///
+    /// No memory is copied, the region shares the stride of its parent.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Horizontal offset in pixels
+    /// * `y` - Vertical offset in pixels
+    /// * `width` - Width of the region in pixels
+    /// * `height` - Height of the region in pixels
+    pub fn sub_view(&self, x: u32, y: u32, width: u32, height: u32) -> Option<ImageView<'a, P, T>> {
+        let (start, end) = region::<P>(self.width, self.height, self.stride, x, y, width, height)?;
+
+        Some(ImageView {
+            _marker: PhantomData,
+            buf: &self.buf[start..end],
+            width,
+            height,
+            row_len: row_len::<P>(width)?,
+            stride: self.stride,
+        })
+    }
+
+    /// Returns an iterator over all samples, excluding padding.
+    ///
+    /// The result can be fed into the [`crate::iter::PixelsExt`] adapter.
+    pub fn samples(&self) -> impl Iterator<Item = T> + 'a {
+        self.rows().flat_map(|row| row.iter().copied())
+    }
+}
+
+/// Mutable view into strided image memory.
+///
+/// This is the mutable counterpart of [`ImageView`]. Its samples can be used as the output of
+/// [`crate::iter::Bytes::write`].
+#[derive(Debug)]
+pub struct ImageViewMut<'a, P, T = u8> {
+    _marker: PhantomData<P>,
+    buf: &'a mut [T],
+    width: u32,
+    height: u32,
+    row_len: usize,
+    stride: usize,
+}
+
+impl<'a, P, T> ImageViewMut<'a, P, T>
+where
+    P: Pixel,
+    T: Copy,
+{
+    /// Returns a mutable view into tightly packed image memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Image memory
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    pub fn new(buf: &'a mut [T], width: u32, height: u32) -> Option<Self> {
+        let stride = row_len::<P>(width)? * mem::size_of::<T>();
+        Self::with_stride(buf, width, height, stride)
+    }
+
+    /// Returns a mutable view into image memory with padded rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Image memory
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    /// * `stride` - Length of a row in bytes, including padding
+    pub fn with_stride(buf: &'a mut [T], width: u32, height: u32, stride: usize) -> Option<Self> {
+        let (row_len, stride) = layout::<P, T>(buf.len(), width, height, stride)?;
+
+        Some(ImageViewMut {
+            _marker: PhantomData,
+            buf,
+            width,
+            height,
+            row_len,
+            stride,
+        })
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the length of a row in bytes, including padding.
+    pub fn stride(&self) -> usize {
+        self.stride * mem::size_of::<T>()
+    }
+
+    /// Returns a read-only view of the same memory.
+    pub fn as_view(&self) -> ImageView<'_, P, T> {
+        ImageView {
+            _marker: PhantomData,
+            buf: self.buf,
+            width: self.width,
+            height: self.height,
+            row_len: self.row_len,
+            stride: self.stride,
+        }
+    }
+
+    /// Returns a mutable view into a rectangular region of the image.
+    ///
+    /// No memory is copied, the region shares the stride of its parent.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Horizontal offset in pixels
+    /// * `y` - Vertical offset in pixels
+    /// * `width` - Width of the region in pixels
+    /// * `height` - Height of the region in pixels
+    pub fn sub_view_mut(
+        &mut self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Option<ImageViewMut<'_, P, T>> {
+        let (start, end) = region::<P>(self.width, self.height, self.stride, x, y, width, height)?;
+
+        Some(ImageViewMut {
+            _marker: PhantomData,
+            buf: &mut self.buf[start..end],
+            width,
+            height,
+            row_len: row_len::<P>(width)?,
+            stride: self.stride,
+        })
+    }
+
+    /// Converts the view into a mutable view of a rectangular region, keeping its lifetime.
+    ///
+    /// See [`ImageViewMut::sub_view_mut`].
+    pub fn into_sub_view_mut(
+        self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Option<ImageViewMut<'a, P, T>> {
+        let (start, end) = region::<P>(self.width, self.height, self.stride, x, y, width, height)?;
+
+        Some(ImageViewMut {
+            _marker: PhantomData,
+            buf: &mut self.buf[start..end],
+            width,
+            height,
+            row_len: row_len::<P>(width)?,
+            stride: self.stride,
+        })
+    }
+
+    /// Returns the samples of a single row, excluding padding.
+    pub fn row_mut(&mut self, i: u32) -> Option<&mut [T]> {
+        if i >= self.height {
+            return None;
+        }
+
+        let offset = i as usize * self.stride;
+        Some(&mut self.buf[offset..offset + self.row_len])
+    }
+
+    /// Returns an iterator over all rows, excluding padding.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let (row_len, stride, height) = (self.row_len, self.stride, self.height as usize);
+        let mut rest = &mut *self.buf;
+        (0..height).map(move |y| {
+            let (row, tail) = mem::take(&mut rest).split_at_mut(row_len);
+            // the last row does not need to be padded
+            if y + 1 < height {
+                rest = &mut tail[stride - row_len..];
+            }
+            row
+        })
+    }
+
+    /// Returns an iterator over all samples, excluding padding.
+    ///
+    /// The result can be passed to [`crate::iter::Bytes::write`].
+    pub fn samples_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.rows_mut().flat_map(|row| row.iter_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Rgb;
+
+    #[test]
+    fn layout() {
+        let buf = [0u8; 2 * 8];
+
+        // tightly packed
+        assert!(ImageView::<Rgb<u8>>::new(&buf[..12], 2, 2).is_some());
+        assert!(ImageView::<Rgb<u8>>::new(&buf[..11], 2, 2).is_none());
+        // padded rows, last row without padding
+        assert!(ImageView::<Rgb<u8>>::with_stride(&buf[..14], 2, 2, 8).is_some());
+        assert!(ImageView::<Rgb<u8>>::with_stride(&buf[..13], 2, 2, 8).is_none());
+        // stride smaller than a row
+        assert!(ImageView::<Rgb<u8>>::with_stride(&buf, 2, 2, 5).is_none());
+        // stride too large to address the last row
+        assert!(ImageView::<Rgb<u8>>::with_stride(&buf, 2, 2, usize::MAX).is_none());
+        assert!(ImageView::<Rgb<u8>>::with_stride(&buf[..6], 2, 1, usize::MAX).is_some());
+    }
+
+    #[test]
+    fn rows() {
+        let buf = [1, 2, 3, 0, 4, 5, 6, 0];
+        let view = ImageView::<Rgb<u8>>::with_stride(&buf, 1, 2, 4).unwrap();
+
+        assert_eq!(view.row(0), Some(&buf[0..3]));
+        assert_eq!(view.row(1), Some(&buf[4..7]));
+        assert_eq!(view.row(2), None);
+        assert!(view.samples().eq([1, 2, 3, 4, 5, 6]));
+    }
+
+    #[test]
+    fn stride_u16() {
+        let buf = [1u16, 2, 3, 0, 4, 5, 6, 0];
+
+        // stride must be given in bytes
+        let view = ImageView::<Rgb<u16>, u16>::with_stride(&buf, 1, 2, 8).unwrap();
+        assert_eq!(view.stride(), 8);
+        assert!(view.samples().eq([1, 2, 3, 4, 5, 6]));
+        assert!(ImageView::<Rgb<u16>, u16>::with_stride(&buf, 1, 2, 7).is_none());
+    }
+
+    #[test]
+    fn sub_view() {
+        #[rustfmt::skip]
+        let buf = [
+            1, 2, 3, 4, 5, 6, 7, 8, 9, 0,
+            10, 11, 12, 13, 14, 15, 16, 17, 18, 0,
+            19, 20, 21, 22, 23, 24, 25, 26, 27, 0,
+        ];
+        let view = ImageView::<Rgb<u8>>::with_stride(&buf, 3, 3, 10).unwrap();
+
+        let sub = view.sub_view(1, 1, 2, 2).unwrap();
+        assert_eq!((sub.width(), sub.height(), sub.stride()), (2, 2, 10));
+        assert!(sub
+            .samples()
+            .eq([13, 14, 15, 16, 17, 18, 22, 23, 24, 25, 26, 27]));
+
+        // nested regions
+        let sub = sub.sub_view(1, 0, 1, 1).unwrap();
+        assert!(sub.samples().eq([16, 17, 18]));
+
+        // out of bounds
+        assert!(view.sub_view(2, 0, 2, 1).is_none());
+        assert!(view.sub_view(0, 3, 1, 1).is_none());
+        assert!(view.sub_view(u32::MAX, 0, 1, 1).is_none());
+    }
+
+    #[test]
+    fn sub_view_empty() {
+        let buf = [1u8; 29];
+        let view = ImageView::<Rgb<u8>>::with_stride(&buf, 3, 3, 10).unwrap();
+
+        // bottom and right edges, the last row is not padded
+        let sub = view.sub_view(0, 3, 3, 0).unwrap();
+        assert_eq!((sub.width(), sub.height()), (3, 0));
+        assert_eq!(sub.samples().count(), 0);
+        let sub = view.sub_view(3, 0, 0, 3).unwrap();
+        assert_eq!((sub.width(), sub.height()), (0, 3));
+        assert_eq!(sub.row(2), Some(&[][..]));
+        assert_eq!(sub.samples().count(), 0);
+        assert!(view.sub_view(3, 3, 0, 0).is_some());
+
+        let mut buf = [1u8; 29];
+        let mut view = ImageViewMut::<Rgb<u8>>::with_stride(&mut buf, 3, 3, 10).unwrap();
+        assert_eq!(
+            view.sub_view_mut(0, 3, 3, 0).unwrap().samples_mut().count(),
+            0
+        );
+        assert_eq!(
+            view.sub_view_mut(3, 0, 0, 3).unwrap().samples_mut().count(),
+            0
+        );
+        assert!(view.into_sub_view_mut(0, 3, 3, 0).is_some());
+    }
+
+    #[test]
+    fn rows_zero_width() {
+        let buf = [1u8; 29];
+        let view = ImageView::<Rgb<u8>>::with_stride(&buf, 3, 3, 10).unwrap();
+
+        let sub = view.sub_view(1, 0, 0, 3).unwrap();
+        assert_eq!(sub.rows().count(), 3);
+        assert!(sub.rows().all(|row| row.is_empty()));
+
+        let mut buf = [1u8; 29];
+        let mut view = ImageViewMut::<Rgb<u8>>::with_stride(&mut buf, 3, 3, 10).unwrap();
+        let mut sub = view.sub_view_mut(3, 0, 0, 3).unwrap();
+        assert_eq!(sub.rows_mut().count(), 3);
+        assert!(sub.rows_mut().all(|row| row.is_empty()));
+    }
+
+    #[test]
+    fn sub_view_mut() {
+        let mut buf = [0u8; 3 * 8];
+        let mut view = ImageViewMut::<Rgb<u8>>::with_stride(&mut buf, 2, 3, 8).unwrap();
+
+        view.sub_view_mut(1, 1, 1, 2)
+            .unwrap()
+            .samples_mut()
+            .for_each(|sample| *sample = 1);
+        #[rustfmt::skip]
+        assert_eq!(buf, [
+            0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 1, 1, 1, 0, 0,
+            0, 0, 0, 1, 1, 1, 0, 0,
+        ]);
+    }
+
+    #[test]
+    fn samples_mut() {
+        let mut buf = [0u8; 8];
+        let mut view = ImageViewMut::<Rgb<u8>>::with_stride(&mut buf, 1, 2, 4).unwrap();
+
+        view.samples_mut()
+            .zip(1..)
+            .for_each(|(sample, i)| *sample = i);
+        assert_eq!(buf, [1, 2, 3, 0, 4, 5, 6, 0]);
+    }
+}
@@ -1,4 +1,6 @@
-use core::{marker::PhantomData, ops::Deref};
+use core::{convert::TryFrom, iter::FusedIterator, marker::PhantomData, ops::Deref};
+
+use crate::{color::Alpha, sample::FromDepth, Error};
 
 /// Adapter which converts a bytestream into a typed pixel stream.
 ///
@@ -16,8 +18,9 @@ pub trait PixelsExt<const C: usize>: Iterator {
 impl<I, const C: usize> PixelsExt<C> for I where I: Iterator {}
 
 pub struct Pixels<T, I, P, const C: usize> {
-    _marker: PhantomData<(T, P)>,
+    _marker: PhantomData<P>,
     iter: I,
+    remainder: Option<([T; C], usize)>,
 }
 
 impl<T, I, P, const C: usize> Pixels<T, I, P, C> {
@@ -25,6 +28,19 @@ impl<T, I, P, const C: usize> Pixels<T, I, P, C> {
         Pixels {
             _marker: PhantomData,
             iter,
+            remainder: None,
+        }
+    }
+
+    /// Returns the trailing samples which do not form a whole pixel.
+    ///
+    /// The samples are recorded once `next` or `nth` reaches the end of the stream or
+    /// `next_back` is called, so a non-empty remainder after iteration indicates a truncated
+    /// buffer.
+    pub fn remainder(&self) -> &[T] {
+        match &self.remainder {
+            Some((chunk, len)) => &chunk[..*len],
+            None => &[],
         }
     }
 }
@@ -38,14 +54,93 @@ where
     type Item = P;
 
     fn next(&mut self) -> Option<Self::Item> {
+        Some(P::from(self.next_chunk()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        (lower / C, upper.map(|upper| upper / C))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // skip in bulk only if the stream is known to hold all skipped samples, otherwise a
+        // trailing partial pixel has to be recorded
+        match n.checked_mul(C) {
+            Some(skip) if skip > 0 && self.iter.size_hint().0 >= skip => {
+                self.iter.nth(skip - 1)?;
+            }
+            _ => {
+                for _ in 0..n {
+                    self.next_chunk()?;
+                }
+            }
+        }
+        self.next()
+    }
+}
+
+impl<T, I, P, const C: usize> Pixels<T, I, P, C>
+where
+    T: Copy,
+    I: Iterator<Item = T>,
+{
+    /// Returns the samples of the next whole pixel, records a trailing partial one.
+    fn next_chunk(&mut self) -> Option<[T; C]> {
         let mut chunk = [self.iter.next()?; C];
         for i in 1..C {
-            chunk[i] = self.iter.next()?;
+            match self.iter.next() {
+                Some(sample) => chunk[i] = sample,
+                None => {
+                    self.remainder = Some((chunk, i));
+                    return None;
+                }
+            }
+        }
+        Some(chunk)
+    }
+}
+
+impl<T, I, P, const C: usize> DoubleEndedIterator for Pixels<T, I, P, C>
+where
+    T: Copy,
+    I: DoubleEndedIterator<Item = T> + ExactSizeIterator,
+    P: From<[T; C]>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // trailing samples which do not form a whole pixel are kept as remainder
+        let rem = self.iter.len() % C;
+        if rem > 0 {
+            let mut chunk = [self.iter.next_back()?; C];
+            for i in (0..rem - 1).rev() {
+                chunk[i] = self.iter.next_back()?;
+            }
+            self.remainder = Some((chunk, rem));
+        }
+
+        let mut chunk = [self.iter.next_back()?; C];
+        for channel in chunk.iter_mut().rev().skip(1) {
+            *channel = self.iter.next_back()?;
         }
         Some(P::from(chunk))
     }
 }
 
+impl<T, I, P, const C: usize> ExactSizeIterator for Pixels<T, I, P, C>
+where
+    T: Copy,
+    I: ExactSizeIterator<Item = T>,
+    P: From<[T; C]>,
+{
+}
+
+impl<T, I, P, const C: usize> FusedIterator for Pixels<T, I, P, C>
+where
+    T: Copy,
+    I: FusedIterator<Item = T>,
+    P: From<[T; C]>,
+{
+}
+
 /// Adapter which converts between color formats.
 ///
 /// The trait is automatically implemented for all pixel types which implement the `From<[T; C]>`
@@ -86,6 +181,406 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         Some(P2::from(self.iter.next()?))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        Some(P2::from(self.iter.nth(n)?))
+    }
+}
+
+impl<I, P, P2> DoubleEndedIterator for ColorConvert<I, P, P2>
+where
+    P2: From<P>,
+    I: DoubleEndedIterator<Item = P>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        Some(P2::from(self.iter.next_back()?))
+    }
+}
+
+impl<I, P, P2> ExactSizeIterator for ColorConvert<I, P, P2>
+where
+    P2: From<P>,
+    I: ExactSizeIterator<Item = P>,
+{
+}
+
+impl<I, P, P2> FusedIterator for ColorConvert<I, P, P2>
+where
+    P2: From<P>,
+    I: FusedIterator<Item = P>,
+{
+}
+
+/// Adapter which converts between color formats and surfaces conversion errors.
+///
+/// The trait is automatically implemented for all pixel types `P` where the target type
+/// implements `TryFrom<P>`. In contrast to [`ColorConvertExt`], each item is a `Result`, so
+/// values which cannot be represented in the target format do not have to panic.
+pub trait TryColorConvertExt: Iterator {
+    fn try_colorconvert<P2>(self) -> TryColorConvert<Self, Self::Item, P2>
+    where
+        Self: Sized,
+        P2: TryFrom<Self::Item>,
+    {
+        TryColorConvert::new(self)
+    }
+}
+
+impl<I> TryColorConvertExt for I where I: Iterator {}
+
+pub struct TryColorConvert<I, P, P2> {
+    _marker: PhantomData<(P, P2)>,
+    iter: I,
+}
+
+impl<I, P, P2> TryColorConvert<I, P, P2> {
+    pub fn new(iter: I) -> Self {
+        TryColorConvert {
+            _marker: PhantomData,
+            iter,
+        }
+    }
+}
+
+impl<I, P, P2> Iterator for TryColorConvert<I, P, P2>
+where
+    P2: TryFrom<P>,
+    I: Iterator<Item = P>,
+{
+    type Item = Result<P2, P2::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(P2::try_from(self.iter.next()?))
+    }
+}
+
+/// Adapter which converts between sample types of the same color format.
+///
+/// The trait is automatically implemented for all pixel types which implement the
+/// [`FromDepth`] trait, e.g. `Rgb<u16>` to `Rgb<u8>`.
+pub trait DepthConvertExt: Iterator {
+    fn depthconvert<P2>(self) -> DepthConvert<Self, Self::Item, P2>
+    where
+        Self: Sized,
+        P2: FromDepth<Self::Item>,
+    {
+        DepthConvert::new(self)
+    }
+}
+
+impl<I> DepthConvertExt for I where I: Iterator {}
+
+pub struct DepthConvert<I, P, P2> {
+    _marker: PhantomData<(P, P2)>,
+    iter: I,
+}
+
+impl<I, P, P2> DepthConvert<I, P, P2> {
+    pub fn new(iter: I) -> Self {
+        DepthConvert {
+            _marker: PhantomData,
+            iter,
+        }
+    }
+}
+
+impl<I, P, P2> Iterator for DepthConvert<I, P, P2>
+where
+    P2: FromDepth<P>,
+    I: Iterator<Item = P>,
+{
+    type Item = P2;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(P2::from_depth(self.iter.next()?))
+    }
+}
+
+/// Adapter which multiplies the color channels of pixels by their alpha value.
+///
+/// The trait is automatically implemented for all pixel types which implement the
+/// [`Alpha`] trait.
+pub trait PremultiplyExt: Iterator {
+    fn premultiply(self) -> Premultiply<Self>
+    where
+        Self: Sized,
+        Self::Item: Alpha,
+    {
+        Premultiply::new(self)
+    }
+}
+
+impl<I> PremultiplyExt for I where I: Iterator {}
+
+pub struct Premultiply<I> {
+    iter: I,
+}
+
+impl<I> Premultiply<I> {
+    pub fn new(iter: I) -> Self {
+        Premultiply { iter }
+    }
+}
+
+impl<I> Iterator for Premultiply<I>
+where
+    I: Iterator,
+    I::Item: Alpha,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.iter.next()?.premultiply())
+    }
+}
+
+/// Adapter which divides the color channels of premultiplied pixels by their alpha value.
+///
+/// The trait is automatically implemented for all pixel types which implement the
+/// [`Alpha`] trait.
+pub trait UnpremultiplyExt: Iterator {
+    fn unpremultiply(self) -> Unpremultiply<Self>
+    where
+        Self: Sized,
+        Self::Item: Alpha,
+    {
+        Unpremultiply::new(self)
+    }
+}
+
+impl<I> UnpremultiplyExt for I where I: Iterator {}
+
+pub struct Unpremultiply<I> {
+    iter: I,
+}
+
+impl<I> Unpremultiply<I> {
+    pub fn new(iter: I) -> Self {
+        Unpremultiply { iter }
+    }
+}
+
+impl<I> Iterator for Unpremultiply<I>
+where
+    I: Iterator,
+    I::Item: Alpha,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.iter.next()?.unpremultiply())
+    }
+}
+
+/// Adapter which composites pixels over a background color, dropping their alpha channel.
+///
+/// The trait is automatically implemented for all pixel types which implement the
+/// [`Alpha`] trait.
+pub trait FlattenAlphaExt: Iterator {
+    fn flatten_alpha(
+        self,
+        background: <Self::Item as Alpha>::Opaque,
+    ) -> FlattenAlpha<Self, <Self::Item as Alpha>::Opaque>
+    where
+        Self: Sized,
+        Self::Item: Alpha,
+    {
+        FlattenAlpha::new(self, background)
+    }
+}
+
+impl<I> FlattenAlphaExt for I where I: Iterator {}
+
+pub struct FlattenAlpha<I, P> {
+    iter: I,
+    background: P,
+}
+
+impl<I, P> FlattenAlpha<I, P> {
+    pub fn new(iter: I, background: P) -> Self {
+        FlattenAlpha { iter, background }
+    }
+}
+
+impl<I, P> Iterator for FlattenAlpha<I, P>
+where
+    I: Iterator,
+    I::Item: Alpha<Opaque = P>,
+    P: Copy,
+{
+    type Item = P;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.iter.next()?.flatten(self.background))
+    }
+}
+
+/// Group layout of MIPI CSI-2 RAW formats with `BITS` bits per sample.
+struct RawGroup<const BITS: u32>;
+
+impl<const BITS: u32> RawGroup<BITS> {
+    /// Number of samples which share a byte for their least significant bits
+    const LEN: usize = {
+        assert!(BITS == 10 || BITS == 12, "RAW bit depth must be 10 or 12");
+        (8 / (BITS - 8)) as usize
+    };
+}
+
+/// Adapter which unpacks MIPI CSI-2 RAW10 and RAW12 bytestreams into samples.
+///
+/// RAW10 packs four samples into five bytes, RAW12 packs two samples into three bytes. The first
+/// bytes of a group hold the most significant bits of each sample, followed by a byte with the
+/// least significant bits of all samples. Unpacked samples hold their value in the lower bits,
+/// e.g. use `.map(U10::from)` to turn them into [`crate::sample::U10`] samples. Incomplete groups
+/// at the end of the stream are dropped.
+pub trait UnpackRawExt: Iterator {
+    fn unpack_raw10(self) -> UnpackRaw<Self, 10>
+    where
+        Self: Sized + Iterator<Item = u8>,
+    {
+        UnpackRaw::new(self)
+    }
+
+    fn unpack_raw12(self) -> UnpackRaw<Self, 12>
+    where
+        Self: Sized + Iterator<Item = u8>,
+    {
+        UnpackRaw::new(self)
+    }
+}
+
+impl<I> UnpackRawExt for I where I: Iterator {}
+
+/// See [`UnpackRawExt`].
+///
+/// Only RAW10 and RAW12 are supported, other bit depths are rejected at compile time:
+///
+/// ```compile_fail
+/// use ffimage::iter::UnpackRaw;
+///
+/// let samples = UnpackRaw::<_, 8>::new([0u8; 4].iter().copied());
+/// ```
+pub struct UnpackRaw<I, const BITS: u32> {
+    iter: I,
+    group: [u16; 4],
+    pos: usize,
+}
+
+impl<I, const BITS: u32> UnpackRaw<I, BITS> {
+    pub fn new(iter: I) -> Self {
+        UnpackRaw {
+            iter,
+            group: [0; 4],
+            pos: RawGroup::<BITS>::LEN,
+        }
+    }
+}
+
+impl<I, const BITS: u32> Iterator for UnpackRaw<I, BITS>
+where
+    I: Iterator<Item = u8>,
+{
+    type Item = u16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = RawGroup::<BITS>::LEN;
+        let lsb_bits = BITS - 8;
+
+        if self.pos == len {
+            for sample in &mut self.group[..len] {
+                *sample = (self.iter.next()? as u16) << lsb_bits;
+            }
+            let lsb = self.iter.next()? as u16;
+            for (i, sample) in self.group[..len].iter_mut().enumerate() {
+                *sample |= (lsb >> (i as u32 * lsb_bits)) & ((1 << lsb_bits) - 1);
+            }
+            self.pos = 0;
+        }
+
+        self.pos += 1;
+        Some(self.group[self.pos - 1])
+    }
+}
+
+/// Adapter which packs samples into MIPI CSI-2 RAW10 and RAW12 bytestreams.
+///
+/// This is the inverse of [`UnpackRawExt`]. Samples are truncated to the bit depth of the format,
+/// an incomplete group at the end of the stream is padded with zero samples.
+pub trait PackRawExt: Iterator {
+    fn pack_raw10(self) -> PackRaw<Self, 10>
+    where
+        Self: Sized + Iterator<Item = u16>,
+    {
+        PackRaw::new(self)
+    }
+
+    fn pack_raw12(self) -> PackRaw<Self, 12>
+    where
+        Self: Sized + Iterator<Item = u16>,
+    {
+        PackRaw::new(self)
+    }
+}
+
+impl<I> PackRawExt for I where I: Iterator {}
+
+/// See [`PackRawExt`].
+///
+/// Only RAW10 and RAW12 are supported, other bit depths are rejected at compile time:
+///
+/// ```compile_fail
+/// use ffimage::iter::PackRaw;
+///
+/// let bytes = PackRaw::<_, 9>::new([0u16; 4].iter().copied());
+/// ```
+pub struct PackRaw<I, const BITS: u32> {
+    iter: I,
+    group: [u8; 5],
+    pos: usize,
+}
+
+impl<I, const BITS: u32> PackRaw<I, BITS> {
+    pub fn new(iter: I) -> Self {
+        PackRaw {
+            iter,
+            group: [0; 5],
+            pos: RawGroup::<BITS>::LEN + 1,
+        }
+    }
+}
+
+impl<I, const BITS: u32> Iterator for PackRaw<I, BITS>
+where
+    I: Iterator<Item = u16>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = RawGroup::<BITS>::LEN;
+        let lsb_bits = BITS - 8;
+
+        if self.pos == len + 1 {
+            let mut lsb = 0;
+            for i in 0..len {
+                let sample = match self.iter.next() {
+                    Some(sample) => sample,
+                    None if i == 0 => return None,
+                    None => 0,
+                };
+                self.group[i] = (sample >> lsb_bits) as u8;
+                lsb |= ((sample & ((1 << lsb_bits) - 1)) as u8) << (i as u32 * lsb_bits);
+            }
+            self.group[len] = lsb;
+            self.pos = 0;
+        }
+
+        self.pos += 1;
+        Some(self.group[self.pos - 1])
+    }
 }
 
 /// Adapter which converts a typed pixel stream into a bytestream.
@@ -129,6 +624,41 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         Some(*self.iter.next()?)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        Some(*self.iter.nth(n)?)
+    }
+}
+
+impl<T, I, const C: usize> DoubleEndedIterator for Bytes<T, I, C>
+where
+    T: Copy,
+    I: DoubleEndedIterator,
+    I::Item: Deref<Target = [T; C]>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        Some(*self.iter.next_back()?)
+    }
+}
+
+impl<T, I, const C: usize> ExactSizeIterator for Bytes<T, I, C>
+where
+    T: Copy,
+    I: ExactSizeIterator,
+    I::Item: Deref<Target = [T; C]>,
+{
+}
+
+impl<T, I, const C: usize> FusedIterator for Bytes<T, I, C>
+where
+    T: Copy,
+    I: FusedIterator,
+    I::Item: Deref<Target = [T; C]>,
+{
 }
 
 impl<'a, T, I, const C: usize> Bytes<T, I, C>
@@ -148,12 +678,49 @@ where
             })
         });
     }
+
+    /// Writes the samples to `out` and returns the number of samples written.
+    ///
+    /// In contrast to [`write`](Self::write), a short output is reported as
+    /// [`Error::LengthMismatch`] instead of panicking. All samples which fit are written in that
+    /// case. Compare the returned number against the length of `out` to detect a long output.
+    ///
+    /// The remaining samples are not consumed to compute the expected length, it is derived from
+    /// the size hint of the source instead. For sources without an exact size, it is a lower
+    /// bound.
+    pub fn try_write<O>(mut self, out: O) -> Result<usize, Error>
+    where
+        O: IntoIterator<Item = &'a mut T>,
+    {
+        let mut out = out.into_iter();
+        let mut written = 0;
+        while let Some(chunk) = self.next() {
+            for (i, channel) in chunk.iter().enumerate() {
+                match out.next() {
+                    Some(sample) => *sample = *channel,
+                    None => {
+                        return Err(Error::LengthMismatch {
+                            expected: (written + C - i)
+                                .saturating_add(self.size_hint().0.saturating_mul(C)),
+                            actual: written,
+                        })
+                    }
+                }
+                written += 1;
+            }
+        }
+
+        Ok(written)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::color::{Bgr, Rgb};
+    use crate::{
+        color::{Bgr, Gray, GrayAlpha, Rgb, Rgba},
+        sample::{U10, U12},
+    };
 
     #[test]
     fn pixels() {
@@ -166,6 +733,155 @@ mod tests {
         assert_eq!(pixels.next(), None);
     }
 
+    #[test]
+    fn pixels_exact_size() {
+        let buf = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let mut pixels = buf.iter().copied().pixels::<Rgb<u8>>();
+
+        assert_eq!(pixels.len(), 3);
+        assert_eq!(pixels.next_back(), Some(Rgb([7, 8, 9])));
+        assert_eq!(pixels.next(), Some(Rgb([1, 2, 3])));
+        assert_eq!(pixels.len(), 1);
+        assert_eq!(pixels.next_back(), Some(Rgb([4, 5, 6])));
+        assert_eq!(pixels.next_back(), None);
+        assert_eq!(pixels.next(), None);
+
+        let mut pixels = buf.iter().copied().pixels::<Rgb<u8>>();
+        assert_eq!(pixels.nth(1), Some(Rgb([4, 5, 6])));
+        assert_eq!(pixels.nth(1), None);
+    }
+
+    #[test]
+    fn remainder() {
+        let buf = [1, 2, 3, 4, 5, 6, 7, 8];
+        let mut pixels = buf.iter().copied().pixels::<Rgb<u8>>();
+
+        assert_eq!(pixels.by_ref().count(), 2);
+        assert_eq!(pixels.remainder(), [7, 8]);
+
+        let mut pixels = buf.iter().copied().pixels::<Rgb<u8>>();
+        assert_eq!(pixels.remainder(), []);
+        assert_eq!(pixels.next_back(), Some(Rgb([4, 5, 6])));
+        assert_eq!(pixels.remainder(), [7, 8]);
+
+        let mut pixels = buf[..6].iter().copied().pixels::<Rgb<u8>>();
+        assert_eq!(pixels.by_ref().count(), 2);
+        assert_eq!(pixels.remainder(), []);
+
+        // skipping over the end
+        let mut pixels = buf.iter().copied().pixels::<Rgb<u8>>();
+        assert_eq!(pixels.nth(2), None);
+        assert_eq!(pixels.remainder(), [7, 8]);
+
+        let mut pixels = buf.iter().copied().pixels::<Rgb<u8>>();
+        assert_eq!(pixels.nth(usize::MAX), None);
+        assert_eq!(pixels.remainder(), [7, 8]);
+
+        // streams without an exact size hint
+        let mut pixels = buf.iter().copied().filter(|_| true).pixels::<Rgb<u8>>();
+        assert_eq!(pixels.nth(1), Some(Rgb([4, 5, 6])));
+        assert_eq!(pixels.nth(1), None);
+        assert_eq!(pixels.remainder(), [7, 8]);
+    }
+
+    #[test]
+    fn try_write() {
+        let buf = [1, 2, 3, 4, 5, 6];
+        let bytes = || buf.iter().copied().pixels::<Rgb<u8>>().bytes();
+
+        let mut out = [0; 6];
+        assert_eq!(bytes().try_write(&mut out), Ok(6));
+        assert_eq!(out, buf);
+
+        let mut out = [0; 8];
+        assert_eq!(bytes().try_write(&mut out), Ok(6));
+        assert_eq!(out, [1, 2, 3, 4, 5, 6, 0, 0]);
+
+        let mut out = [0; 4];
+        assert_eq!(
+            bytes().try_write(&mut out),
+            Err(Error::LengthMismatch {
+                expected: 6,
+                actual: 4
+            })
+        );
+        assert_eq!(out, [1, 2, 3, 4]);
+
+        // unbounded sources are not drained
+        let mut out = [0; 4];
+        assert_eq!(
+            core::iter::repeat(Rgb::<u8>([1, 2, 3]))
+                .bytes()
+                .try_write(&mut out),
+            Err(Error::LengthMismatch {
+                expected: usize::MAX,
+                actual: 4
+            })
+        );
+        assert_eq!(out, [1, 2, 3, 1]);
+    }
+
+    #[test]
+    fn rev() {
+        let buf = [1, 2, 3, 4, 5, 6];
+        let mut out = [0; 6];
+        let pixels = buf
+            .iter()
+            .copied()
+            .pixels::<Rgb<u8>>()
+            .colorconvert::<Bgr<u8>>()
+            .rev()
+            .bytes();
+        assert_eq!(pixels.len(), 2);
+        pixels.write(&mut out);
+        assert_eq!(out, [6, 5, 4, 3, 2, 1]);
+
+        let mut pixels = buf
+            .iter()
+            .copied()
+            .pixels::<Gray<u8>>()
+            .colorconvert::<Rgb<u8>>();
+        assert_eq!(pixels.nth(4), Some(Rgb([5, 5, 5])));
+        assert_eq!(pixels.size_hint(), (1, Some(1)));
+    }
+
+    #[test]
+    fn try_colorconvert() {
+        #[derive(Debug, PartialEq)]
+        struct Mono(bool);
+
+        impl TryFrom<Gray<u8>> for Mono {
+            type Error = crate::Error;
+
+            fn try_from(gray: Gray<u8>) -> Result<Self, Self::Error> {
+                match gray[0] {
+                    0 => Ok(Mono(false)),
+                    255 => Ok(Mono(true)),
+                    _ => Err(crate::Error::OutOfRange),
+                }
+            }
+        }
+
+        let buf = [0, 128, 255];
+        let mut pixels = buf
+            .iter()
+            .copied()
+            .pixels::<Gray<u8>>()
+            .try_colorconvert::<Mono>();
+
+        assert_eq!(pixels.next(), Some(Ok(Mono(false))));
+        assert_eq!(pixels.next(), Some(Err(crate::Error::OutOfRange)));
+        assert_eq!(pixels.next(), Some(Ok(Mono(true))));
+        assert_eq!(pixels.next(), None);
+
+        let mut pixels = buf
+            .iter()
+            .copied()
+            .pixels::<Gray<u8>>()
+            .try_colorconvert::<Rgb<u8>>();
+        assert_eq!(pixels.next(), Some(Ok(Rgb([0, 0, 0]))));
+    }
+
     #[test]
     fn bytes() {
         let buf = [1, 2, 3, 4, 5, 6, 7, 8, 9];
@@ -178,4 +894,135 @@ mod tests {
             .write(&mut out);
         assert_eq!(out, [3, 2, 1, 6, 5, 4, 9, 8, 7]);
     }
+
+    #[test]
+    fn depthconvert() {
+        let buf = [0x0000, 0x0800, 0x0fff];
+        let mut out = [0; 3];
+        buf.iter()
+            .copied()
+            .map(U12::from)
+            .pixels::<Rgb<U12>>()
+            .depthconvert::<Rgb<u8>>()
+            .colorconvert::<Bgr<u8>>()
+            .bytes()
+            .write(&mut out);
+        assert_eq!(out, [255, 128, 0]);
+    }
+
+    #[cfg(feature = "f16")]
+    #[test]
+    fn half() {
+        use crate::sample::f16;
+
+        let buf = [f16::ONE, f16::ZERO, f16::ONE];
+        let mut out = [0u8; 3];
+        buf.iter()
+            .copied()
+            .pixels::<Rgb<f16>>()
+            .colorconvert::<Bgr<f16>>()
+            .depthconvert::<Bgr<u8>>()
+            .bytes()
+            .write(&mut out);
+        assert_eq!(out, [255, 0, 255]);
+
+        let gray: Gray<f16> = buf
+            .iter()
+            .copied()
+            .pixels::<Rgb<f16>>()
+            .colorconvert()
+            .next()
+            .unwrap();
+        assert!((gray[0].to_f32() - 0.2848).abs() < 0.001);
+    }
+
+    #[test]
+    fn premultiply() {
+        let buf = [255, 100, 0, 128, 20, 128];
+        let mut out = [0; 6];
+        buf.iter()
+            .copied()
+            .pixels::<Rgba<u8>>()
+            .premultiply()
+            .bytes()
+            .write(&mut out[..4]);
+        buf.iter()
+            .copied()
+            .skip(4)
+            .pixels::<GrayAlpha<u8>>()
+            .premultiply()
+            .unpremultiply()
+            .bytes()
+            .write(&mut out[4..]);
+        assert_eq!(out, [128, 50, 0, 128, 20, 128]);
+    }
+
+    #[test]
+    fn flatten_alpha() {
+        let buf = [255, 255, 255, 0, 255, 255, 255, 255];
+        let mut out = [0; 2];
+        buf.iter()
+            .copied()
+            .pixels::<Rgba<u8>>()
+            .flatten_alpha(Rgb([0, 0, 0]))
+            .colorconvert::<Gray<u8>>()
+            .bytes()
+            .write(&mut out);
+        assert_eq!(out, [0, 255]);
+    }
+
+    #[test]
+    fn unpack_raw() {
+        let buf = [0xff, 0x00, 0x55, 0xaa, 0x93, 0xab, 0x12, 0x3c];
+        let mut samples = buf.iter().copied().unpack_raw10();
+        assert_eq!(samples.next(), Some(0x3ff));
+        assert_eq!(samples.next(), Some(0x000));
+        assert_eq!(samples.next(), Some(0x155));
+        assert_eq!(samples.next(), Some(0x2aa));
+        // incomplete group
+        assert_eq!(samples.next(), None);
+
+        let mut samples = buf[5..].iter().copied().unpack_raw12();
+        assert_eq!(samples.next(), Some(0xabc));
+        assert_eq!(samples.next(), Some(0x123));
+        assert_eq!(samples.next(), None);
+    }
+
+    #[test]
+    fn pack_raw() {
+        let mut out = [0; 8];
+        out.iter_mut()
+            .zip([0x3ff, 0x000, 0x155, 0x2aa].iter().copied().pack_raw10())
+            .for_each(|(out, byte)| *out = byte);
+        out[5..]
+            .iter_mut()
+            .zip([0xabc, 0x123].iter().copied().pack_raw12())
+            .for_each(|(out, byte)| *out = byte);
+        assert_eq!(out, [0xff, 0x00, 0x55, 0xaa, 0x93, 0xab, 0x12, 0x3c]);
+
+        // incomplete groups are padded
+        let mut bytes = [0x3ff, 0x3ff].iter().copied().pack_raw10();
+        assert!(bytes.by_ref().take(5).eq([0xff, 0xff, 0x00, 0x00, 0x0f]));
+        assert_eq!(bytes.next(), None);
+        assert_eq!([0u16; 0].iter().copied().pack_raw12().next(), None);
+    }
+
+    #[test]
+    fn raw_round_trip() {
+        assert!((0..1024).pack_raw10().unpack_raw10().eq(0..1024));
+        assert!((0..4096).pack_raw12().unpack_raw12().eq(0..4096));
+
+        let mut out = [0u16; 2];
+        [0xff, 0x00, 0x55, 0xaa, 0x93]
+            .iter()
+            .copied()
+            .unpack_raw10()
+            .map(U10::from)
+            .pixels::<Gray<U10>>()
+            .depthconvert::<Gray<u16>>()
+            .take(2)
+            .bytes()
+            .write(&mut out);
+        assert_eq!(out, [u16::MAX, 0]);
+    }
 }
@@ -2,7 +2,12 @@
 //!
 //! It features basic pixel abstractions and allows converting between color formats. New pixel
 //! types can easily be defined and used with the existing abstractions. By default, RGB / BGR,
-//! Grayscale as well as YUV 4:4:4, 4:2:2, 4:2:0p (planar) are supported.
+//! RGBA / BGRA / ARGB / ABGR, packed RGB565 / BGR565 / RGB555 / ARGB1555 and 10-bit RGB10A2 /
+//! X2RGB10, Grayscale (with and without alpha), HSV / HSL, linear light RGB, CIE XYZ / L*a*b* /
+//! L*u*v* as well as YUV 4:4:4, 4:2:2, 4:2:0p (planar) are supported.
+//!
+//! With the `derive` feature, `#[derive(Pixel)]` generates the boilerplate of custom pixel types,
+//! see the [ffimage_derive](https://docs.rs/ffimage_derive) crate.
 //!
 //! Additional documentation can currently also be found in the
 //! [README.md file which is most easily viewed on github](https://github.com/raymanfx/ffimage/blob/master/README.md).
@@ -32,6 +37,11 @@
 
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 /// Generic pixel attributes
 pub trait Pixel {
     /// Number of channels for this pixel
@@ -40,5 +50,20 @@ pub trait Pixel {
     const SUBPIXELS: u8 = 1;
 }
 
+pub mod bayer;
+pub mod cast;
 pub mod color;
+pub mod convert;
+pub mod format;
+pub mod image;
 pub mod iter;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod sample;
+pub mod simd;
+
+mod error;
+pub use error::Error;
+
+#[cfg(feature = "derive")]
+pub use ffimage_derive::Pixel;
@@ -0,0 +1,154 @@
+//! Parallel frame conversion.
+//!
+//! Frames are split into bands of rows which are converted in parallel on the rayon thread
+//! pool. Each band is processed by the serial kernels of the [`simd`](crate::simd) module, so
+//! the output is identical to the serial path:
+//!
+//! ```
+//! use ffimage::{color::Bt709, par, simd};
+//!
+//! let rgb: Vec<u8> = (0..64 * 48 * 3).map(|i| i as u8).collect();
+//! let mut serial = vec![0; 64 * 48];
+//! let mut parallel = vec![0; 64 * 48];
+//!
+//! simd::rgb_to_gray::<Bt709>(&rgb, &mut serial);
+//! par::rgb_to_gray::<Bt709>(&rgb, 64, 48, &mut parallel);
+//! assert_eq!(serial, parallel);
+//! ```
+
+use core::ops::Range;
+
+use rayon::prelude::*;
+
+use crate::{color::Luma, simd};
+
+/// Returns the number of rows per band, which is a multiple of `align`.
+fn band_rows(height: usize, align: usize) -> usize {
+    let threads = rayon::current_num_threads().max(1);
+    let rows = (height + threads - 1) / threads;
+    ((rows + align - 1) / align * align).max(align)
+}
+
+/// Runs `f` on bands of rows in parallel.
+///
+/// `src` and `dst` must hold `height` rows of `src_stride` and `dst_stride` samples. Each band
+/// but the last one has a multiple of `align` rows, which allows to keep subsampled chroma rows
+/// together. `f` is called with the samples of the band in `src` and `dst` and the range of rows
+/// covered by the band.
+///
+/// # Panics
+///
+/// Panics if `src` or `dst` do not hold exactly `height` rows.
+pub fn bands<T, U, F>(
+    src: &[T],
+    src_stride: usize,
+    dst: &mut [U],
+    dst_stride: usize,
+    height: usize,
+    align: usize,
+    f: F,
+) where
+    T: Sync,
+    U: Send,
+    F: Fn(&[T], &mut [U], Range<usize>) + Sync + Send,
+{
+    assert_eq!(src.len(), src_stride * height);
+    assert_eq!(dst.len(), dst_stride * height);
+    if src.is_empty() || dst.is_empty() {
+        return;
+    }
+
+    let rows = band_rows(height, align);
+    src.par_chunks(src_stride * rows)
+        .zip(dst.par_chunks_mut(dst_stride * rows))
+        .enumerate()
+        .for_each(|(i, (src, dst))| {
+            let start = i * rows;
+            f(src, dst, start..start + src.len() / src_stride)
+        })
+}
+
+/// Converts a frame of RGB to BGR pixels or vice versa.
+///
+/// # Panics
+///
+/// Panics if `src` or `dst` do not hold `width * height` pixels.
+pub fn rgb_to_bgr(src: &[u8], width: u32, height: u32, dst: &mut [u8]) {
+    let stride = width as usize * 3;
+    bands(
+        src,
+        stride,
+        dst,
+        stride,
+        height as usize,
+        1,
+        |src, dst, _| simd::rgb_to_bgr(src, dst),
+    )
+}
+
+/// Converts a frame of RGB to grayscale pixels using the luma coefficients of `L`.
+///
+/// # Panics
+///
+/// Panics if `src` or `dst` do not hold `width * height` pixels.
+pub fn rgb_to_gray<L: Luma>(src: &[u8], width: u32, height: u32, dst: &mut [u8]) {
+    let width = width as usize;
+    bands(
+        src,
+        width * 3,
+        dst,
+        width,
+        height as usize,
+        1,
+        |src, dst, _| simd::rgb_to_gray::<L>(src, dst),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Bt601;
+    use alloc::{vec, vec::Vec};
+
+    #[test]
+    fn bands() {
+        let src: Vec<u32> = (0..7 * 13).collect();
+        let mut dst = vec![0; 7 * 13];
+
+        super::bands(&src, 7, &mut dst, 7, 13, 2, |src, dst, rows| {
+            assert_eq!(src.len(), rows.len() * 7);
+            assert_eq!(src[0], rows.start as u32 * 7);
+            assert!(rows.start % 2 == 0 && (rows.end % 2 == 0 || rows.end == 13));
+            dst.copy_from_slice(src);
+        });
+        assert_eq!(src, dst);
+    }
+
+    #[test]
+    fn rgb_to_bgr() {
+        let rgb: Vec<u8> = (0..67 * 35 * 3).map(|i| (i * 7) as u8).collect();
+        let mut serial = vec![0; rgb.len()];
+        let mut parallel = vec![0; rgb.len()];
+
+        simd::rgb_to_bgr(&rgb, &mut serial);
+        super::rgb_to_bgr(&rgb, 67, 35, &mut parallel);
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn rgb_to_gray() {
+        let rgb: Vec<u8> = (0..67 * 35 * 3).map(|i| (i * 7) as u8).collect();
+        let mut serial = vec![0; 67 * 35];
+        let mut parallel = vec![0; 67 * 35];
+
+        simd::rgb_to_gray::<Bt601>(&rgb, &mut serial);
+        super::rgb_to_gray::<Bt601>(&rgb, 67, 35, &mut parallel);
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    #[should_panic]
+    fn mismatch() {
+        super::rgb_to_bgr(&[0; 12], 2, 3, &mut [0; 12]);
+    }
+}
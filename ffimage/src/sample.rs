@@ -0,0 +1,200 @@
+/// Pixel channel sample with a normalized range.
+///
+/// Integer samples cover the range from zero to their maximum value, float samples cover the
+/// range from 0.0 to 1.0. Converting between sample types rescales the value, e.g. `255u8`
+/// becomes `65535u16` or `1.0f32`.
+pub trait Sample: Copy {
+    /// Sample value which represents zero intensity
+    const MIN: Self;
+    /// Sample value which represents full intensity
+    const MAX: Self;
+
+    /// Returns the sample value normalized to the range of 0.0 to 1.0.
+    fn to_normalized(self) -> f32;
+
+    /// Returns the sample value for a normalized value.
+    ///
+    /// Values outside of the range of 0.0 to 1.0 are clamped, integer samples are rounded to the
+    /// nearest code value.
+    fn from_normalized(value: f32) -> Self;
+
+    /// Returns the sample rescaled to another sample type.
+    fn to_sample<U: Sample>(self) -> U {
+        U::from_normalized(self.to_normalized())
+    }
+}
+
+impl Sample for u8 {
+    const MIN: Self = 0;
+    const MAX: Self = u8::MAX;
+
+    fn to_normalized(self) -> f32 {
+        self as f32 / Self::MAX as f32
+    }
+
+    fn from_normalized(value: f32) -> Self {
+        (value.clamp(0.0, 1.0) * Self::MAX as f32 + 0.5) as u8
+    }
+}
+
+impl Sample for u16 {
+    const MIN: Self = 0;
+    const MAX: Self = u16::MAX;
+
+    fn to_normalized(self) -> f32 {
+        self as f32 / Self::MAX as f32
+    }
+
+    fn from_normalized(value: f32) -> Self {
+        (value.clamp(0.0, 1.0) * Self::MAX as f32 + 0.5) as u16
+    }
+}
+
+impl Sample for f32 {
+    const MIN: Self = 0.0;
+    const MAX: Self = 1.0;
+
+    fn to_normalized(self) -> f32 {
+        self
+    }
+
+    fn from_normalized(value: f32) -> Self {
+        value.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(feature = "f16")]
+pub use half::f16;
+
+#[cfg(feature = "f16")]
+impl Sample for f16 {
+    const MIN: Self = f16::ZERO;
+    const MAX: Self = f16::ONE;
+
+    fn to_normalized(self) -> f32 {
+        self.to_f32()
+    }
+
+    fn from_normalized(value: f32) -> Self {
+        f16::from_f32(value.clamp(0.0, 1.0))
+    }
+}
+
+/// Sample with less than 16 significant bits, stored in the lower bits of a `u16`.
+///
+/// Sensors with 10, 12 or 14 bits of depth usually deliver their samples this way. The depth
+/// `N` must be in the range of 1 to 16, other values are rejected at compile time:
+///
+/// ```compile_fail
+/// use ffimage::sample::{Bits, Sample};
+///
+/// let max = Bits::<17>::MAX;
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bits<const N: u32>(pub u16);
+
+/// 10 bit sample in a `u16` container
+pub type U10 = Bits<10>;
+
+/// 12 bit sample in a `u16` container
+pub type U12 = Bits<12>;
+
+/// 14 bit sample in a `u16` container
+pub type U14 = Bits<14>;
+
+impl<const N: u32> Bits<N> {
+    /// Number of significant bits, validated at compile time
+    const BITS: u32 = {
+        assert!(
+            N >= 1 && N <= 16,
+            "sample depth must be between 1 and 16 bits"
+        );
+        N
+    };
+}
+
+impl<const N: u32> From<u16> for Bits<N> {
+    fn from(value: u16) -> Self {
+        Bits(value)
+    }
+}
+
+impl<const N: u32> From<Bits<N>> for u16 {
+    fn from(value: Bits<N>) -> Self {
+        value.0
+    }
+}
+
+impl<const N: u32> Sample for Bits<N> {
+    const MIN: Self = Bits(0);
+    const MAX: Self = Bits(((1u32 << Self::BITS) - 1) as u16);
+
+    fn to_normalized(self) -> f32 {
+        self.0 as f32 / Self::MAX.0 as f32
+    }
+
+    fn from_normalized(value: f32) -> Self {
+        Bits((value.clamp(0.0, 1.0) * Self::MAX.0 as f32 + 0.5) as u16)
+    }
+}
+
+/// Normalized sample depth conversion between pixels.
+///
+/// This is the counterpart of `From` for pixels of the same color format but different sample
+/// types. All channels are rescaled by [`Sample::to_sample`].
+pub trait FromDepth<P> {
+    /// Returns the pixel with rescaled channel samples.
+    fn from_depth(pixel: P) -> Self;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_sample() {
+        assert_eq!(255u8.to_sample::<u16>(), u16::MAX);
+        assert_eq!(128u8.to_sample::<u16>(), 128 * 257);
+        assert_eq!(u16::MAX.to_sample::<u8>(), 255);
+        assert_eq!(0x7f80u16.to_sample::<u8>(), 127);
+        assert_eq!(255u8.to_sample::<f32>(), 1.0);
+        assert_eq!(0.5f32.to_sample::<u8>(), 128);
+        assert_eq!(2.0f32.to_sample::<u8>(), 255);
+        assert_eq!((-1.0f32).to_sample::<u16>(), 0);
+    }
+
+    #[test]
+    fn round_trip() {
+        for v in 0..=255u8 {
+            assert_eq!(v.to_sample::<u16>().to_sample::<u8>(), v);
+            assert_eq!(v.to_sample::<f32>().to_sample::<u8>(), v);
+            assert_eq!(v.to_sample::<U12>().to_sample::<u8>(), v);
+        }
+        for v in 0..=4095u16 {
+            assert_eq!(Bits::<12>(v).to_sample::<u16>().to_sample::<U12>(), Bits(v));
+        }
+    }
+
+    #[cfg(feature = "f16")]
+    #[test]
+    fn half() {
+        assert_eq!(<f16 as Sample>::MAX.to_sample::<u8>(), 255);
+        assert_eq!(128u8.to_sample::<f16>(), f16::from_f32(128.0 / 255.0));
+        assert_eq!(f16::from_f32(0.25).to_sample::<f32>(), 0.25);
+        for v in 0..=255u8 {
+            assert_eq!(v.to_sample::<f16>().to_sample::<u8>(), v);
+        }
+    }
+
+    #[test]
+    fn bits() {
+        assert_eq!(U10::MAX, Bits(1023));
+        assert_eq!(U12::MAX.to_sample::<u8>(), 255);
+        assert_eq!(Bits::<12>(2048).to_sample::<u8>(), 128);
+        assert_eq!(255u8.to_sample::<U10>(), Bits(1023));
+        assert_eq!(U14::MAX.to_sample::<u16>(), u16::MAX);
+        assert_eq!(Bits::<1>::MAX, Bits(1));
+        assert_eq!(Bits::<16>::MAX, Bits(u16::MAX));
+        assert_eq!(Bits::<16>(u16::MAX).to_sample::<u8>(), 255);
+    }
+}
@@ -0,0 +1,59 @@
+//! aarch64 kernels.
+
+use core::arch::aarch64::*;
+
+/// Returns the number of converted pixels.
+pub(crate) unsafe fn rgb_to_bgr_neon(src: &[u8], dst: &mut [u8]) -> usize {
+    let mut n = 0;
+    while (n + 16) * 3 <= src.len() {
+        let v = vld3q_u8(src.as_ptr().add(n * 3));
+        vst3q_u8(dst.as_mut_ptr().add(n * 3), uint8x16x3_t(v.2, v.1, v.0));
+        n += 16;
+    }
+
+    n
+}
+
+/// Returns the 16.16 fixed-point luma of eight 16 bit RGB samples.
+#[inline(always)]
+unsafe fn luma(r: uint16x8_t, g: uint16x8_t, b: uint16x8_t, k: [uint16x4_t; 3]) -> uint8x8_t {
+    let round = vdupq_n_u32(1 << 15);
+    let lo = vmlal_u16(round, vget_low_u16(r), k[0]);
+    let lo = vmlal_u16(lo, vget_low_u16(g), k[1]);
+    let lo = vmlal_u16(lo, vget_low_u16(b), k[2]);
+    let hi = vmlal_u16(round, vget_high_u16(r), k[0]);
+    let hi = vmlal_u16(hi, vget_high_u16(g), k[1]);
+    let hi = vmlal_u16(hi, vget_high_u16(b), k[2]);
+
+    vqmovn_u16(vcombine_u16(vshrn_n_u32(lo, 16), vshrn_n_u32(hi, 16)))
+}
+
+/// Returns the number of converted pixels, the weights must fit into 16 bits.
+pub(crate) unsafe fn rgb_to_gray_neon(src: &[u8], dst: &mut [u8], weights: [u32; 3]) -> usize {
+    let k = [
+        vdup_n_u16(weights[0] as u16),
+        vdup_n_u16(weights[1] as u16),
+        vdup_n_u16(weights[2] as u16),
+    ];
+
+    let mut n = 0;
+    while (n + 16) * 3 <= src.len() {
+        let v = vld3q_u8(src.as_ptr().add(n * 3));
+        let lo = luma(
+            vmovl_u8(vget_low_u8(v.0)),
+            vmovl_u8(vget_low_u8(v.1)),
+            vmovl_u8(vget_low_u8(v.2)),
+            k,
+        );
+        let hi = luma(
+            vmovl_u8(vget_high_u8(v.0)),
+            vmovl_u8(vget_high_u8(v.1)),
+            vmovl_u8(vget_high_u8(v.2)),
+            k,
+        );
+        vst1q_u8(dst.as_mut_ptr().add(n), vcombine_u8(lo, hi));
+        n += 16;
+    }
+
+    n
+}
@@ -0,0 +1,254 @@
+//! SIMD accelerated conversion kernels.
+//!
+//! The kernels operate on whole byte slices of 8 bit pixels and pick the best instruction set
+//! supported by the CPU at runtime. With the `std` feature enabled, the CPU features are
+//! detected by the standard library, otherwise they are queried via CPUID. The scalar `From`
+//! implementations serve as fallback and process any trailing pixels, the results are
+//! bit-identical to them in any case:
+//!
+//! ```
+//! use ffimage::{color::{Bt709, Gray, Luma, Rgb}, simd};
+//!
+//! let rgb = [[10u8, 200, 30]; 100].concat();
+//! let mut gray = [0u8; 100];
+//! simd::rgb_to_gray::<Bt709>(&rgb, &mut gray);
+//!
+//! assert_eq!(Gray([gray[99]]), Bt709::luma(Rgb::<u8>([10, 200, 30])));
+//! ```
+
+use crate::color::{Bgr, Luma, Rgb};
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+// Shared with the kernels of ffimage_yuv, not part of the public API.
+#[cfg(target_arch = "x86_64")]
+#[doc(hidden)]
+pub mod x86;
+
+/// Instruction set extensions the kernels can be dispatched to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Isa {
+    /// Plain scalar code
+    Scalar,
+    /// SSE2, which is available on every x86_64 CPU
+    #[cfg(target_arch = "x86_64")]
+    Sse2,
+    /// SSSE3, adds byte shuffles
+    #[cfg(target_arch = "x86_64")]
+    Ssse3,
+    /// AVX2, adds 256 bit integer operations
+    #[cfg(target_arch = "x86_64")]
+    Avx2,
+    /// Advanced SIMD, which is available on every aarch64 CPU
+    #[cfg(target_arch = "aarch64")]
+    Neon,
+}
+
+impl Isa {
+    /// All instruction sets of the target architecture, ordered from worst to best
+    pub const ALL: &'static [Isa] = &[
+        Isa::Scalar,
+        #[cfg(target_arch = "x86_64")]
+        Isa::Sse2,
+        #[cfg(target_arch = "x86_64")]
+        Isa::Ssse3,
+        #[cfg(target_arch = "x86_64")]
+        Isa::Avx2,
+        #[cfg(target_arch = "aarch64")]
+        Isa::Neon,
+    ];
+
+    /// Returns the best instruction set supported by the CPU.
+    pub fn detect() -> Self {
+        Isa::ALL
+            .iter()
+            .rev()
+            .copied()
+            .find(|isa| isa.is_supported())
+            .unwrap_or(Isa::Scalar)
+    }
+
+    /// Returns whether the CPU supports the instruction set.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Isa::Scalar => true,
+            #[cfg(target_arch = "x86_64")]
+            Isa::Sse2 => true,
+            #[cfg(all(target_arch = "x86_64", feature = "std"))]
+            Isa::Ssse3 => std::is_x86_feature_detected!("ssse3"),
+            #[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+            Isa::Ssse3 => x86::has_ssse3(),
+            #[cfg(all(target_arch = "x86_64", feature = "std"))]
+            Isa::Avx2 => std::is_x86_feature_detected!("avx2"),
+            #[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+            Isa::Avx2 => x86::has_avx2(),
+            #[cfg(target_arch = "aarch64")]
+            Isa::Neon => true,
+        }
+    }
+}
+
+/// Converts RGB to BGR pixels or vice versa.
+///
+/// # Panics
+///
+/// Panics if `src` does not contain whole pixels or if `dst` has a different length.
+pub fn rgb_to_bgr(src: &[u8], dst: &mut [u8]) {
+    rgb_to_bgr_with(Isa::detect(), src, dst)
+}
+
+fn rgb_to_bgr_with(isa: Isa, src: &[u8], dst: &mut [u8]) {
+    assert_eq!(src.len() % 3, 0);
+    assert_eq!(src.len(), dst.len());
+
+    let n = match isa {
+        #[cfg(target_arch = "x86_64")]
+        Isa::Avx2 => unsafe { x86::rgb_to_bgr_avx2(src, dst) },
+        #[cfg(target_arch = "x86_64")]
+        Isa::Ssse3 => unsafe { x86::rgb_to_bgr_ssse3(src, dst) },
+        #[cfg(target_arch = "aarch64")]
+        Isa::Neon => unsafe { aarch64::rgb_to_bgr_neon(src, dst) },
+        _ => 0,
+    };
+
+    for (src, dst) in src[n * 3..]
+        .chunks_exact(3)
+        .zip(dst[n * 3..].chunks_exact_mut(3))
+    {
+        let bgr = Bgr::<u8>::from(Rgb::<u8>([src[0], src[1], src[2]]));
+        dst.copy_from_slice(&bgr.0);
+    }
+}
+
+/// Converts RGB to grayscale pixels using the luma coefficients of `L`.
+///
+/// # Panics
+///
+/// Panics if `src` does not contain whole pixels or if `dst` does not hold exactly one sample
+/// per pixel.
+pub fn rgb_to_gray<L: Luma>(src: &[u8], dst: &mut [u8]) {
+    rgb_to_gray_with::<L>(Isa::detect(), src, dst)
+}
+
+fn rgb_to_gray_with<L: Luma>(isa: Isa, src: &[u8], dst: &mut [u8]) {
+    assert_eq!(src.len() % 3, 0);
+    assert_eq!(src.len() / 3, dst.len());
+
+    // the kernels multiply with 16 bit weights, larger ones (i.e. 1.0) take the scalar path
+    let weights = [L::KR_FIXED, L::KG_FIXED, L::KB_FIXED];
+    let isa = if weights.iter().all(|&k| k <= u16::MAX as u32) {
+        isa
+    } else {
+        Isa::Scalar
+    };
+
+    let n = match isa {
+        #[cfg(target_arch = "x86_64")]
+        Isa::Avx2 => unsafe { x86::rgb_to_gray_avx2(src, dst, weights) },
+        #[cfg(target_arch = "x86_64")]
+        Isa::Sse2 | Isa::Ssse3 => unsafe { x86::rgb_to_gray_sse2(src, dst, weights) },
+        #[cfg(target_arch = "aarch64")]
+        Isa::Neon => unsafe { aarch64::rgb_to_gray_neon(src, dst, weights) },
+        _ => 0,
+    };
+
+    for (src, dst) in src[n * 3..].chunks_exact(3).zip(dst[n..].iter_mut()) {
+        *dst = L::luma(Rgb::<u8>([src[0], src[1], src[2]]))[0];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{Average, Bt2020, Bt601, Bt709};
+
+    /// Returns pseudo-random bytes from a linear congruential generator.
+    fn random<const N: usize>(seed: u32) -> [u8; N] {
+        let mut state = seed;
+        let mut buf = [0; N];
+        for x in buf.iter_mut() {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            *x = (state >> 16) as u8;
+        }
+        buf
+    }
+
+    #[test]
+    fn detect() {
+        assert!(Isa::detect().is_supported());
+        assert!(Isa::Scalar.is_supported());
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn cpuid() {
+        extern crate std;
+
+        assert_eq!(x86::has_ssse3(), std::is_x86_feature_detected!("ssse3"));
+        assert_eq!(x86::has_avx2(), std::is_x86_feature_detected!("avx2"));
+    }
+
+    #[test]
+    fn rgb_to_bgr() {
+        // odd length to exercise the scalar tail
+        let src: [u8; 3 * 301] = random(1);
+
+        let mut expected = [0u8; 3 * 301];
+        rgb_to_bgr_with(Isa::Scalar, &src, &mut expected);
+        assert_eq!(expected[..3], [src[2], src[1], src[0]]);
+
+        for &isa in Isa::ALL.iter().filter(|isa| isa.is_supported()) {
+            let mut bgr = [0u8; 3 * 301];
+            rgb_to_bgr_with(isa, &src, &mut bgr);
+            assert_eq!(bgr[..], expected[..], "{:?}", isa);
+        }
+    }
+
+    fn rgb_to_gray_isa<L: Luma>() {
+        let mut src: [u8; 3 * 301] = random(2);
+        // extremes
+        src[..6].copy_from_slice(&[255, 255, 255, 0, 0, 0]);
+
+        let mut expected = [0u8; 301];
+        rgb_to_gray_with::<L>(Isa::Scalar, &src, &mut expected);
+        assert_eq!(expected[..2], [255, 0]);
+
+        for &isa in Isa::ALL.iter().filter(|isa| isa.is_supported()) {
+            let mut gray = [0u8; 301];
+            rgb_to_gray_with::<L>(isa, &src, &mut gray);
+            assert_eq!(gray[..], expected[..], "{:?}", isa);
+        }
+    }
+
+    #[test]
+    fn rgb_to_gray() {
+        rgb_to_gray_isa::<Bt601>();
+        rgb_to_gray_isa::<Bt709>();
+        rgb_to_gray_isa::<Bt2020>();
+        rgb_to_gray_isa::<Average>();
+    }
+
+    #[test]
+    fn rgb_to_gray_unit_weight() {
+        struct Red;
+
+        impl Luma for Red {
+            const KR: f32 = 1.0;
+            const KG: f32 = 0.0;
+            const KB: f32 = 0.0;
+        }
+
+        rgb_to_gray_isa::<Red>();
+
+        let src: [u8; 3 * 64] = random(3);
+        let mut gray = [0u8; 64];
+        super::rgb_to_gray::<Red>(&src, &mut gray);
+        assert!(gray.iter().zip(src.chunks(3)).all(|(y, rgb)| *y == rgb[0]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn rgb_to_gray_mismatch() {
+        super::rgb_to_gray::<Bt709>(&[0; 6], &mut [0; 3]);
+    }
+}
@@ -0,0 +1,293 @@
+//! x86_64 kernels and building blocks.
+//!
+//! The building blocks are shared with the kernels of ffimage_yuv. They are not part of the
+//! public API and may change in any release. SSE2 is part of the x86_64 baseline, so they can be
+//! called from any function.
+
+use core::arch::x86_64::*;
+
+/// Returns the result of the CPUID instruction for a leaf and sub-leaf.
+#[cfg(any(test, not(feature = "std")))]
+#[allow(unused_unsafe)]
+fn cpuid(leaf: u32, sub_leaf: u32) -> CpuidResult {
+    // SAFETY: CPUID is available on every x86_64 CPU, recent compilers consider it safe
+    unsafe { __cpuid_count(leaf, sub_leaf) }
+}
+
+/// Returns whether the CPU supports SSSE3, without relying on the standard library.
+#[cfg(any(test, not(feature = "std")))]
+pub(crate) fn has_ssse3() -> bool {
+    cpuid(1, 0).ecx & (1 << 9) != 0
+}
+
+/// Returns whether the CPU and the OS support AVX2, without relying on the standard library.
+#[cfg(any(test, not(feature = "std")))]
+pub(crate) fn has_avx2() -> bool {
+    if cpuid(0, 0).eax < 7 {
+        return false;
+    }
+
+    // the OS has to save the AVX registers (XSAVE enabled, XMM and YMM state in XCR0)
+    let leaf1 = cpuid(1, 0);
+    if leaf1.ecx & (1 << 27) == 0 || leaf1.ecx & (1 << 28) == 0 {
+        return false;
+    }
+    // SAFETY: OSXSAVE is set, so XGETBV is available
+    if unsafe { xcr0() } & 0b110 != 0b110 {
+        return false;
+    }
+
+    cpuid(7, 0).ebx & (1 << 5) != 0
+}
+
+#[cfg(any(test, not(feature = "std")))]
+#[target_feature(enable = "xsave")]
+unsafe fn xcr0() -> u64 {
+    _xgetbv(0)
+}
+
+/// Loads 32 pixels of three interleaved 8 bit channels and splits them into planes.
+///
+/// Each plane consists of two vectors, holding the samples of the first and the last 16 pixels.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reading 96 bytes.
+#[inline(always)]
+pub unsafe fn load3(ptr: *const u8) -> [[__m128i; 2]; 3] {
+    let mut v = [_mm_setzero_si128(); 6];
+    for (i, v) in v.iter_mut().enumerate() {
+        *v = _mm_loadu_si128(ptr.add(i * 16) as *const __m128i);
+    }
+
+    // Five rounds of interleaving the first with the second half of the bytes turn the stride
+    // of three into a stride of one.
+    for _ in 0..5 {
+        v = [
+            _mm_unpacklo_epi8(v[0], v[3]),
+            _mm_unpackhi_epi8(v[0], v[3]),
+            _mm_unpacklo_epi8(v[1], v[4]),
+            _mm_unpackhi_epi8(v[1], v[4]),
+            _mm_unpacklo_epi8(v[2], v[5]),
+            _mm_unpackhi_epi8(v[2], v[5]),
+        ];
+    }
+
+    [[v[0], v[1]], [v[2], v[3]], [v[4], v[5]]]
+}
+
+/// Interleaves 32 samples of three 8 bit planes and stores them as pixels.
+///
+/// This is the inverse of [`load3`].
+///
+/// # Safety
+///
+/// `ptr` must be valid for writing 96 bytes.
+#[inline(always)]
+pub unsafe fn store3(ptr: *mut u8, planes: [[__m128i; 2]; 3]) {
+    let mut v = [
+        planes[0][0],
+        planes[0][1],
+        planes[1][0],
+        planes[1][1],
+        planes[2][0],
+        planes[2][1],
+    ];
+
+    for _ in 0..5 {
+        v = [
+            even(v[0], v[1]),
+            even(v[2], v[3]),
+            even(v[4], v[5]),
+            odd(v[0], v[1]),
+            odd(v[2], v[3]),
+            odd(v[4], v[5]),
+        ];
+    }
+
+    for (i, v) in v.iter().enumerate() {
+        _mm_storeu_si128(ptr.add(i * 16) as *mut __m128i, *v);
+    }
+}
+
+/// Returns the bytes at even positions of `a` followed by those of `b`.
+///
+/// # Safety
+///
+/// Always safe to call, SSE2 is available on every x86_64 CPU.
+#[inline(always)]
+pub unsafe fn even(a: __m128i, b: __m128i) -> __m128i {
+    let mask = _mm_set1_epi16(0xff);
+    _mm_packus_epi16(_mm_and_si128(a, mask), _mm_and_si128(b, mask))
+}
+
+/// Returns the bytes at odd positions of `a` followed by those of `b`.
+///
+/// # Safety
+///
+/// Always safe to call, SSE2 is available on every x86_64 CPU.
+#[inline(always)]
+pub unsafe fn odd(a: __m128i, b: __m128i) -> __m128i {
+    _mm_packus_epi16(_mm_srli_epi16(a, 8), _mm_srli_epi16(b, 8))
+}
+
+/// Swaps the first and third channel of five pixels, the last byte is kept.
+#[inline(always)]
+unsafe fn swap_mask() -> __m128i {
+    _mm_setr_epi8(2, 1, 0, 5, 4, 3, 8, 7, 6, 11, 10, 9, 14, 13, 12, 15)
+}
+
+/// Returns the number of converted pixels.
+#[target_feature(enable = "ssse3")]
+pub(crate) unsafe fn rgb_to_bgr_ssse3(src: &[u8], dst: &mut [u8]) -> usize {
+    let mask = swap_mask();
+
+    // The last byte of each store is garbage and fixed up by the following store.
+    let mut i = 0;
+    while i + 16 <= src.len() {
+        let v = _mm_loadu_si128(src.as_ptr().add(i) as *const __m128i);
+        _mm_storeu_si128(
+            dst.as_mut_ptr().add(i) as *mut __m128i,
+            _mm_shuffle_epi8(v, mask),
+        );
+        i += 15;
+    }
+
+    i / 3
+}
+
+/// Returns the number of converted pixels.
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn rgb_to_bgr_avx2(src: &[u8], dst: &mut [u8]) -> usize {
+    let mask = _mm256_broadcastsi128_si256(swap_mask());
+
+    let mut i = 0;
+    while i + 31 <= src.len() {
+        let v = _mm256_set_m128i(
+            _mm_loadu_si128(src.as_ptr().add(i + 15) as *const __m128i),
+            _mm_loadu_si128(src.as_ptr().add(i) as *const __m128i),
+        );
+        let v = _mm256_shuffle_epi8(v, mask);
+        _mm_storeu_si128(
+            dst.as_mut_ptr().add(i) as *mut __m128i,
+            _mm256_castsi256_si128(v),
+        );
+        _mm_storeu_si128(
+            dst.as_mut_ptr().add(i + 15) as *mut __m128i,
+            _mm256_extracti128_si256(v, 1),
+        );
+        i += 30;
+    }
+
+    i / 3
+}
+
+/// Returns the 16.16 fixed-point luma of eight 16 bit RGB samples.
+#[inline(always)]
+unsafe fn luma_sse2(rgb: [__m128i; 3], weights: [__m128i; 3]) -> __m128i {
+    let mut lo = _mm_set1_epi32(1 << 15);
+    let mut hi = lo;
+    for (x, k) in rgb.iter().zip(weights.iter()) {
+        // full 32 bit products of unsigned 16 bit numbers
+        let pl = _mm_mullo_epi16(*x, *k);
+        let ph = _mm_mulhi_epu16(*x, *k);
+        lo = _mm_add_epi32(lo, _mm_unpacklo_epi16(pl, ph));
+        hi = _mm_add_epi32(hi, _mm_unpackhi_epi16(pl, ph));
+    }
+
+    _mm_packs_epi32(_mm_srli_epi32(lo, 16), _mm_srli_epi32(hi, 16))
+}
+
+/// Returns the number of converted pixels, the weights must fit into 16 bits.
+pub(crate) unsafe fn rgb_to_gray_sse2(src: &[u8], dst: &mut [u8], weights: [u32; 3]) -> usize {
+    let zero = _mm_setzero_si128();
+    let weights = weights.map(|k| _mm_set1_epi16(k as u16 as i16));
+
+    let mut n = 0;
+    while (n + 32) * 3 <= src.len() {
+        let [r, g, b] = load3(src.as_ptr().add(n * 3));
+        for i in 0..2 {
+            let lo = luma_sse2(
+                [
+                    _mm_unpacklo_epi8(r[i], zero),
+                    _mm_unpacklo_epi8(g[i], zero),
+                    _mm_unpacklo_epi8(b[i], zero),
+                ],
+                weights,
+            );
+            let hi = luma_sse2(
+                [
+                    _mm_unpackhi_epi8(r[i], zero),
+                    _mm_unpackhi_epi8(g[i], zero),
+                    _mm_unpackhi_epi8(b[i], zero),
+                ],
+                weights,
+            );
+            _mm_storeu_si128(
+                dst.as_mut_ptr().add(n + i * 16) as *mut __m128i,
+                _mm_packus_epi16(lo, hi),
+            );
+        }
+        n += 32;
+    }
+
+    n
+}
+
+/// Returns the 16.16 fixed-point luma of sixteen 16 bit RGB samples.
+#[inline(always)]
+unsafe fn luma_avx2(rgb: [__m256i; 3], weights: [__m256i; 3]) -> __m256i {
+    let mut lo = _mm256_set1_epi32(1 << 15);
+    let mut hi = lo;
+    for (x, k) in rgb.iter().zip(weights.iter()) {
+        let pl = _mm256_mullo_epi16(*x, *k);
+        let ph = _mm256_mulhi_epu16(*x, *k);
+        lo = _mm256_add_epi32(lo, _mm256_unpacklo_epi16(pl, ph));
+        hi = _mm256_add_epi32(hi, _mm256_unpackhi_epi16(pl, ph));
+    }
+
+    _mm256_packs_epi32(_mm256_srli_epi32(lo, 16), _mm256_srli_epi32(hi, 16))
+}
+
+/// Returns the number of converted pixels, the weights must fit into 16 bits.
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn rgb_to_gray_avx2(src: &[u8], dst: &mut [u8], weights: [u32; 3]) -> usize {
+    let zero = _mm256_setzero_si256();
+    let weights = [
+        _mm256_set1_epi16(weights[0] as u16 as i16),
+        _mm256_set1_epi16(weights[1] as u16 as i16),
+        _mm256_set1_epi16(weights[2] as u16 as i16),
+    ];
+
+    let mut n = 0;
+    while (n + 32) * 3 <= src.len() {
+        // unpacking and packing happens within the 128 bit lanes, so the pixel order is kept
+        let [r, g, b] = load3(src.as_ptr().add(n * 3));
+        let r = _mm256_set_m128i(r[1], r[0]);
+        let g = _mm256_set_m128i(g[1], g[0]);
+        let b = _mm256_set_m128i(b[1], b[0]);
+        let lo = luma_avx2(
+            [
+                _mm256_unpacklo_epi8(r, zero),
+                _mm256_unpacklo_epi8(g, zero),
+                _mm256_unpacklo_epi8(b, zero),
+            ],
+            weights,
+        );
+        let hi = luma_avx2(
+            [
+                _mm256_unpackhi_epi8(r, zero),
+                _mm256_unpackhi_epi8(g, zero),
+                _mm256_unpackhi_epi8(b, zero),
+            ],
+            weights,
+        );
+        _mm256_storeu_si256(
+            dst.as_mut_ptr().add(n) as *mut __m256i,
+            _mm256_packus_epi16(lo, hi),
+        );
+        n += 32;
+    }
+
+    n
+}
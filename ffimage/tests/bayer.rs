@@ -0,0 +1,72 @@
+#![cfg(feature = "alloc")]
+
+use ffimage::{
+    bayer::{demosaic, mosaic, CfaPattern, Demosaic},
+    color::{Gray, Rgb},
+    image::ImageBuffer,
+};
+
+const WIDTH: u32 = 48;
+const HEIGHT: u32 = 48;
+
+/// Returns a synthetic frame with smooth gradients in all channels.
+fn gradient() -> ImageBuffer<Rgb<u8>, u8> {
+    let mut buf = ImageBuffer::<Rgb<u8>, u8>::new(WIDTH, HEIGHT).unwrap();
+    let mut view = buf.as_view_mut();
+    for y in 0..HEIGHT {
+        let row = view.row_mut(y).unwrap();
+        for (x, pix) in row.chunks_exact_mut(3).enumerate() {
+            let x = x as u32;
+            pix[0] = (4 * x) as u8;
+            pix[1] = (4 * y) as u8;
+            pix[2] = (255 - 2 * (x + y)) as u8;
+        }
+    }
+    buf
+}
+
+/// Returns the maximum channel error over the frame and over the inner frame, excluding a border
+/// of two pixels.
+fn round_trip(pattern: CfaPattern, method: Demosaic) -> (u8, u8) {
+    let rgb = gradient();
+    let mut raw = ImageBuffer::<Gray<u8>, u8>::new(WIDTH, HEIGHT).unwrap();
+    let mut out = ImageBuffer::<Rgb<u8>, u8>::new(WIDTH, HEIGHT).unwrap();
+
+    mosaic(rgb.as_view(), &mut raw.as_view_mut(), pattern);
+    demosaic(raw.as_view(), &mut out.as_view_mut(), pattern, method);
+
+    let (mut max, mut inner) = (0, 0);
+    for (y, (a, b)) in rgb.as_view().rows().zip(out.as_view().rows()).enumerate() {
+        for (x, (a, b)) in a.chunks_exact(3).zip(b.chunks_exact(3)).enumerate() {
+            let err = (0..3).map(|i| a[i].abs_diff(b[i])).max().unwrap();
+            max = max.max(err);
+            if (2..WIDTH as usize - 2).contains(&x) && (2..HEIGHT as usize - 2).contains(&y) {
+                inner = inner.max(err);
+            }
+        }
+    }
+    (max, inner)
+}
+
+#[test]
+fn demosaic_round_trip() {
+    for pattern in [
+        CfaPattern::Rggb,
+        CfaPattern::Bggr,
+        CfaPattern::Grbg,
+        CfaPattern::Gbrg,
+    ] {
+        // nearest neighbor is off by up to one pixel, i.e. the steepest gradient
+        let (max, _) = round_trip(pattern, Demosaic::Nearest);
+        assert!(max <= 4);
+
+        // linear gradients are reconstructed exactly, except for the mirrored borders
+        let (max, inner) = round_trip(pattern, Demosaic::Bilinear);
+        assert!(max <= 4);
+        assert_eq!(inner, 0);
+
+        let (max, inner) = round_trip(pattern, Demosaic::Malvar);
+        assert!(max <= 8);
+        assert_eq!(inner, 0);
+    }
+}
@@ -1,6 +1,30 @@
-use ffimage::color::{Gray, Rgb};
+use core::ops::RangeInclusive;
+
+use ffimage::{
+    color::{
+        Average, Bgr, Bt2020, Bt601, Bt709, Gray, Hsl, Hsl180, Hsv, Hsv180, Lab, LinearRgb, Luma,
+        Luv, Rgb, Xyz, D50,
+    },
+    iter::ColorConvertExt,
+};
+
+fn make_range(val: u8, delta: u8) -> RangeInclusive<u8> {
+    let lower = val.saturating_sub(delta);
+    let upper = val.saturating_add(delta);
+
+    lower..=upper
+}
+
+fn rgb_samples() -> impl Iterator<Item = Rgb<u8>> {
+    (0..=255u8).step_by(15).flat_map(|r| {
+        (0..=255u8)
+            .step_by(15)
+            .flat_map(move |g| (0..=255u8).step_by(15).map(move |b| Rgb([r, g, b])))
+    })
+}
 
 #[test]
+#[allow(clippy::redundant_closure, clippy::useless_conversion)]
 fn convert_rgb_to_gray() {
     let rgb = vec![Rgb::<u8>([10, 10, 10]); 10];
     let gray: Vec<Gray<u8>> = rgb
@@ -12,7 +36,7 @@ fn convert_rgb_to_gray() {
     rgb.into_iter()
         .zip(gray.into_iter())
         .for_each(|(rgb, gray)| {
-            // rec601 luma
+            // rec709 luma
             let y =
                 (0.2126 * rgb[0] as f32 + 0.7152 * rgb[1] as f32 + 0.0722 * rgb[2] as f32) as u8;
             assert_eq!(gray, Gray([y]));
@@ -20,6 +44,33 @@ fn convert_rgb_to_gray() {
 }
 
 #[test]
+fn convert_rgb_to_gray_fixed_point() {
+    fn check<L: Luma>() {
+        // compare the fixed-point integer path against the float reference
+        for r in (0..=255).step_by(3) {
+            for g in (0..=255).step_by(3) {
+                for b in (0..=255).step_by(3) {
+                    let y = L::luma(Rgb::<u8>([r, g, b]))[0] as f32;
+                    let reference = L::luma(Rgb::<f32>([r as f32, g as f32, b as f32]))[0];
+                    assert!((y - reference).abs() <= 1.0);
+
+                    let (r, g, b) = (r as u16 * 257, g as u16 * 257, b as u16 * 257);
+                    let y = L::luma(Rgb::<u16>([r, g, b]))[0] as f64;
+                    let reference = L::luma(Rgb::<f64>([r as f64, g as f64, b as f64]))[0];
+                    assert!((y - reference).abs() <= 1.0);
+                }
+            }
+        }
+    }
+
+    check::<Bt601>();
+    check::<Bt709>();
+    check::<Bt2020>();
+    check::<Average>();
+}
+
+#[test]
+#[allow(clippy::redundant_closure, clippy::useless_conversion)]
 fn convert_gray_to_rgb() {
     let gray = vec![Gray::<u8>([10]); 10];
     let rgb: Vec<Rgb<u8>> = gray
@@ -34,3 +85,99 @@ fn convert_gray_to_rgb() {
             assert_eq!(rgb, Rgb([gray[0], gray[0], gray[0]]));
         });
 }
+
+#[test]
+fn convert_rgb_to_hsv_to_rgb() {
+    rgb_samples().for_each(|rgb_in| {
+        let rgb_out = Rgb::<u8>::from(Hsv::<u8>::from(rgb_in));
+        let bgr_out = Bgr::<u8>::from(Hsv180::<u8>::from(rgb_in));
+
+        // 8-bit hue and saturation quantization loses some precision
+        for i in 0..3 {
+            assert!(make_range(rgb_in[i], 5).contains(&rgb_out[i]));
+            assert!(make_range(rgb_in[i], 5).contains(&bgr_out[2 - i]));
+        }
+
+        let rgb_in = Rgb::<f32>([rgb_in[0] as f32, rgb_in[1] as f32, rgb_in[2] as f32]);
+        let rgb_in = Rgb::<f32>([rgb_in[0] / 255.0, rgb_in[1] / 255.0, rgb_in[2] / 255.0]);
+        let rgb_out = Rgb::<f32>::from(Hsv::<f32>::from(rgb_in));
+        for i in 0..3 {
+            assert!((rgb_in[i] - rgb_out[i]).abs() < 1e-5);
+        }
+    });
+}
+
+#[test]
+fn convert_rgb_to_hsl_to_rgb() {
+    rgb_samples().for_each(|rgb_in| {
+        let rgb_out = Rgb::<u8>::from(Hsl::<u8>::from(rgb_in));
+        let bgr_out = Bgr::<u8>::from(Hsl180::<u8>::from(rgb_in));
+
+        // 8-bit hue and saturation quantization loses some precision
+        for i in 0..3 {
+            assert!(make_range(rgb_in[i], 5).contains(&rgb_out[i]));
+            assert!(make_range(rgb_in[i], 5).contains(&bgr_out[2 - i]));
+        }
+
+        let rgb_in = Rgb::<f32>([rgb_in[0] as f32, rgb_in[1] as f32, rgb_in[2] as f32]);
+        let rgb_in = Rgb::<f32>([rgb_in[0] / 255.0, rgb_in[1] / 255.0, rgb_in[2] / 255.0]);
+        let rgb_out = Rgb::<f32>::from(Hsl::<f32>::from(rgb_in));
+        for i in 0..3 {
+            assert!((rgb_in[i] - rgb_out[i]).abs() < 1e-5);
+        }
+    });
+}
+
+#[test]
+fn convert_rgb_to_cie_to_rgb() {
+    let rgb: Vec<Rgb<u8>> = rgb_samples().collect();
+
+    let lab: Vec<Rgb<u8>> = rgb
+        .iter()
+        .copied()
+        .colorconvert::<Lab<f32>>()
+        .colorconvert::<Rgb<u8>>()
+        .collect();
+    let lab_d50: Vec<Rgb<u8>> = rgb
+        .iter()
+        .copied()
+        .colorconvert::<Lab<f32, D50>>()
+        .colorconvert::<Rgb<u8>>()
+        .collect();
+    let luv: Vec<Rgb<u8>> = rgb
+        .iter()
+        .copied()
+        .colorconvert::<Luv<f32>>()
+        .colorconvert::<Rgb<u8>>()
+        .collect();
+    let xyz: Vec<Rgb<u8>> = rgb
+        .iter()
+        .copied()
+        .colorconvert::<Xyz<f32>>()
+        .colorconvert::<Lab<f32>>()
+        .colorconvert::<Xyz<f32>>()
+        .colorconvert::<Rgb<u8>>()
+        .collect();
+
+    assert_eq!(lab, rgb);
+    assert_eq!(lab_d50, rgb);
+    assert_eq!(luv, rgb);
+    assert_eq!(xyz, rgb);
+}
+
+#[test]
+fn convert_rgb_to_linear_to_rgb() {
+    let rgb: Vec<Rgb<u8>> = rgb_samples().collect();
+    let linear: Vec<LinearRgb<f32>> = rgb.iter().copied().colorconvert().collect();
+    let out: Vec<Rgb<u8>> = linear.iter().copied().colorconvert().collect();
+
+    assert_eq!(out, rgb);
+
+    // averaging in linear light keeps the perceived brightness
+    let avg = LinearRgb::<f32>::from([
+        (linear[0][0] + linear[linear.len() - 1][0]) / 2.0,
+        (linear[0][1] + linear[linear.len() - 1][1]) / 2.0,
+        (linear[0][2] + linear[linear.len() - 1][2]) / 2.0,
+    ]);
+    assert_eq!(Rgb::<u8>::from(avg), Rgb([188, 188, 188]));
+}